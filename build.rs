@@ -0,0 +1,22 @@
+use std::{env, process::Command};
+
+// Embeds the git commit and build time reported by `GET /version`.
+fn main() {
+    let git_sha = env::var("GIT_SHA")
+        .ok()
+        .or_else(|| {
+            Command::new("git")
+                .args(["rev-parse", "HEAD"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=GIT_SHA={}", git_sha);
+    println!("cargo:rustc-env=BUILD_TIME={}", chrono::Utc::now().to_rfc3339());
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+}
@@ -1,6 +1,13 @@
 pub use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DbBackend;
 
 mod m20220101_000001_create_product_and_item;
+mod m20261019_000001_create_api_key;
+mod m20261019_000002_create_idempotency_key;
+mod m20261019_000003_create_audit_log;
+mod m20261019_000004_create_outbox;
+mod m20261019_000005_create_webhook;
+mod m20261019_000006_add_idempotency_key_lock;
 
 pub struct Migrator;
 
@@ -9,6 +16,28 @@ impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
         vec![
             Box::new(m20220101_000001_create_product_and_item::Migration),
+            Box::new(m20261019_000001_create_api_key::Migration),
+            Box::new(m20261019_000002_create_idempotency_key::Migration),
+            Box::new(m20261019_000003_create_audit_log::Migration),
+            Box::new(m20261019_000004_create_outbox::Migration),
+            Box::new(m20261019_000005_create_webhook::Migration),
+            Box::new(m20261019_000006_add_idempotency_key_lock::Migration),
         ]
     }
 }
+
+/// Creates the Postgres schema the migrations run in when it does not exist yet.
+/// Other backends have no schemas, so this is a no-op for them.
+pub async fn create_schema<C: ConnectionTrait>(db: &C, schema: &str) -> Result<(), DbErr> {
+    if db.get_database_backend() != DbBackend::Postgres {
+        return Ok(());
+    }
+
+    let sql = format!(
+        "CREATE SCHEMA IF NOT EXISTS \"{}\"",
+        schema.trim_matches('"').replace('"', "\"\"")
+    );
+    db.execute_unprepared(&sql).await?;
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApiKey::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ApiKey::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ApiKey::Name).string().not_null())
+                    .col(ColumnDef::new(ApiKey::Prefix).string().not_null().unique_key())
+                    .col(ColumnDef::new(ApiKey::SecretHash).string().not_null())
+                    // Space-separated permissions
+                    .col(ColumnDef::new(ApiKey::Scopes).string().not_null())
+                    .col(ColumnDef::new(ApiKey::ExpiresAt).date_time())
+                    .col(ColumnDef::new(ApiKey::LastUsedAt).date_time())
+                    .col(ColumnDef::new(ApiKey::RevokedAt).date_time())
+                    .col(ColumnDef::new(ApiKey::CreatedAt).date_time().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ApiKey::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ApiKey {
+    Table,
+    Id,
+    Name,
+    Prefix,
+    SecretHash,
+    Scopes,
+    ExpiresAt,
+    LastUsedAt,
+    RevokedAt,
+    CreatedAt,
+}
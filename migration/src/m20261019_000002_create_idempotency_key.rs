@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(IdempotencyKey::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(IdempotencyKey::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(IdempotencyKey::Subject).string().not_null())
+                    .col(ColumnDef::new(IdempotencyKey::Key).string().not_null())
+                    .col(
+                        ColumnDef::new(IdempotencyKey::Fingerprint)
+                            .string()
+                            .not_null(),
+                    )
+                    // Both stay empty while the first request is still running
+                    .col(ColumnDef::new(IdempotencyKey::StatusCode).integer())
+                    .col(ColumnDef::new(IdempotencyKey::ResponseBody).text())
+                    .col(
+                        ColumnDef::new(IdempotencyKey::CreatedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_idempotency_key_subject_key")
+                    .table(IdempotencyKey::Table)
+                    .col(IdempotencyKey::Subject)
+                    .col(IdempotencyKey::Key)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IdempotencyKey::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum IdempotencyKey {
+    Table,
+    Id,
+    Subject,
+    Key,
+    Fingerprint,
+    StatusCode,
+    ResponseBody,
+    CreatedAt,
+}
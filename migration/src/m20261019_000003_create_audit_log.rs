@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditLog::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditLog::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuditLog::Actor).string().not_null())
+                    .col(ColumnDef::new(AuditLog::Action).string().not_null())
+                    .col(ColumnDef::new(AuditLog::EntityType).string().not_null())
+                    .col(ColumnDef::new(AuditLog::EntityId).integer().not_null())
+                    // Missing before a create and after a delete
+                    .col(ColumnDef::new(AuditLog::Before).json())
+                    .col(ColumnDef::new(AuditLog::After).json())
+                    .col(ColumnDef::new(AuditLog::RequestId).string())
+                    .col(ColumnDef::new(AuditLog::CreatedAt).date_time().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_log_entity")
+                    .table(AuditLog::Table)
+                    .col(AuditLog::EntityType)
+                    .col(AuditLog::EntityId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditLog::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum AuditLog {
+    Table,
+    Id,
+    Actor,
+    Action,
+    EntityType,
+    EntityId,
+    Before,
+    After,
+    RequestId,
+    CreatedAt,
+}
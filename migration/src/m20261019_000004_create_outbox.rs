@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Outbox::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Outbox::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Outbox::EventType).string().not_null())
+                    .col(ColumnDef::new(Outbox::ProductId).integer().not_null())
+                    .col(ColumnDef::new(Outbox::Payload).json().not_null())
+                    .col(ColumnDef::new(Outbox::CreatedAt).date_time().not_null())
+                    // Failed events wait until then before the next attempt
+                    .col(ColumnDef::new(Outbox::AvailableAt).date_time().not_null())
+                    .col(
+                        ColumnDef::new(Outbox::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(Outbox::LastError).text())
+                    .col(ColumnDef::new(Outbox::SentAt).date_time())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_outbox_pending")
+                    .table(Outbox::Table)
+                    .col(Outbox::SentAt)
+                    .col(Outbox::AvailableAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Outbox::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Outbox {
+    Table,
+    Id,
+    EventType,
+    ProductId,
+    Payload,
+    CreatedAt,
+    AvailableAt,
+    Attempts,
+    LastError,
+    SentAt,
+}
@@ -0,0 +1,178 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookSubscription::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WebhookSubscription::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WebhookSubscription::Url).string().not_null())
+                    // Space-separated domain event types
+                    .col(
+                        ColumnDef::new(WebhookSubscription::EventTypes)
+                            .string()
+                            .not_null(),
+                    )
+                    // Kept in plaintext, it is needed to sign every delivery
+                    .col(
+                        ColumnDef::new(WebhookSubscription::Secret)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookSubscription::Active)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookSubscription::CreatedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookSubscription::UpdatedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookDelivery::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookDelivery::SubscriptionId)
+                            .integer()
+                            .not_null(),
+                    )
+                    // ID of the outbox event being delivered
+                    .col(
+                        ColumnDef::new(WebhookDelivery::EventId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookDelivery::EventType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::Payload).json().not_null())
+                    // `pending`, `delivered` or `dead`
+                    .col(ColumnDef::new(WebhookDelivery::Status).string().not_null())
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::ResponseStatus).integer())
+                    .col(ColumnDef::new(WebhookDelivery::LastError).text())
+                    .col(
+                        ColumnDef::new(WebhookDelivery::NextAttemptAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookDelivery::CreatedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::DeliveredAt).date_time())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_webhook_delivery_subscription")
+                            .from(WebhookDelivery::Table, WebhookDelivery::SubscriptionId)
+                            .to(WebhookSubscription::Table, WebhookSubscription::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // An event redelivered by the outbox is queued only once per subscription
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_webhook_delivery_event")
+                    .table(WebhookDelivery::Table)
+                    .col(WebhookDelivery::SubscriptionId)
+                    .col(WebhookDelivery::EventId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_webhook_delivery_due")
+                    .table(WebhookDelivery::Table)
+                    .col(WebhookDelivery::Status)
+                    .col(WebhookDelivery::NextAttemptAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WebhookDelivery::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(WebhookSubscription::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum WebhookSubscription {
+    Table,
+    Id,
+    Url,
+    EventTypes,
+    Secret,
+    Active,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum WebhookDelivery {
+    Table,
+    Id,
+    SubscriptionId,
+    EventId,
+    EventType,
+    Payload,
+    Status,
+    Attempts,
+    ResponseStatus,
+    LastError,
+    NextAttemptAt,
+    CreatedAt,
+    DeliveredAt,
+}
@@ -0,0 +1,57 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Until then the key belongs to the running request; afterwards a
+        // retry may take it over, e.g. when the first request was dropped
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(IdempotencyKey::Table)
+                    .add_column(ColumnDef::new(IdempotencyKey::LockedUntil).date_time())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_idempotency_key_created_at")
+                    .table(IdempotencyKey::Table)
+                    .col(IdempotencyKey::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_idempotency_key_created_at")
+                    .table(IdempotencyKey::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(IdempotencyKey::Table)
+                    .drop_column(IdempotencyKey::LockedUntil)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum IdempotencyKey {
+    Table,
+    LockedUntil,
+    CreatedAt,
+}
@@ -1,6 +1,13 @@
-use sea_orm_migration::prelude::*;
+use sea_orm_migration::{prelude::*, sea_orm::Database};
 
 #[async_std::main]
 async fn main() {
-    cli::run_cli(migration::Migrator).await;
+    // The CLI points the search path at DATABASE_SCHEMA; make sure it exists first
+    cli::run_cli_with_connection(migration::Migrator, |options| async move {
+        let db = Database::connect(options).await?;
+        let schema = std::env::var("DATABASE_SCHEMA").unwrap_or_else(|_| "public".to_owned());
+        migration::create_schema(&db, &schema).await?;
+        Ok(db)
+    })
+    .await;
 }
@@ -0,0 +1,766 @@
+use std::{
+    collections::HashMap,
+    env, fmt, fs,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::Path,
+    str::FromStr,
+    time::Duration,
+};
+
+use axum::http::{HeaderName, HeaderValue, Method};
+use dotenv::dotenv;
+use jsonwebtoken::jwk::JwkSet;
+
+/// Config file read when `CONFIG_FILE` is not set; it is optional.
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+/// Every setting the service understands. Each one can be given as an
+/// environment variable of that name or as a lowercase key in the TOML file.
+const SETTINGS: [&str; 38] = [
+    "HOST",
+    "PORT",
+    "SHUTDOWN_TIMEOUT_SECS",
+    "DATABASE_URL",
+    "DATABASE_SCHEMA",
+    "DATABASE_MAX_CONNECTIONS",
+    "DATABASE_MIN_CONNECTIONS",
+    "DATABASE_CONNECT_TIMEOUT_SECS",
+    "DATABASE_ACQUIRE_TIMEOUT_SECS",
+    "DATABASE_IDLE_TIMEOUT_SECS",
+    "DATABASE_LOG_STATEMENTS",
+    "DATABASE_STARTUP_TIMEOUT_SECS",
+    "AUTO_MIGRATE",
+    "CORS_ORIGINS",
+    "CORS_METHODS",
+    "CORS_HEADERS",
+    "CORS_ALLOW_CREDENTIALS",
+    "CORS_MAX_AGE_SECS",
+    "RATE_LIMIT_READ_PER_MINUTE",
+    "RATE_LIMIT_WRITE_PER_MINUTE",
+    "AUTH_FAILURES_PER_MINUTE",
+    "TRUSTED_PROXY_HOPS",
+    "MAX_BODY_BYTES",
+    "BULK_CONCURRENCY_LIMIT",
+    "OUTBOX_POLL_INTERVAL_SECS",
+    "OUTBOX_BATCH_SIZE",
+    "WEBHOOK_TIMEOUT_SECS",
+    "WEBHOOK_MAX_ATTEMPTS",
+    "WEBHOOK_ALLOW_PRIVATE_TARGETS",
+    "EVENT_STREAM_HISTORY",
+    "EVENT_STREAM_KEEP_ALIVE_SECS",
+    "LOG_FORMAT",
+    "OTEL_EXPORTER_OTLP_ENDPOINT",
+    "OTEL_SERVICE_NAME",
+    "JWT_SECRET",
+    "JWT_JWKS_FILE",
+    "JWT_ISSUER",
+    "JWT_AUDIENCE",
+];
+
+/// Settings the service is started with.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub host: IpAddr,
+    pub port: u16,
+    /// Time in-flight requests get to finish once a shutdown signal arrives.
+    pub shutdown_timeout: Duration,
+    pub database: DatabaseConfig,
+    /// Applies pending migrations before the server starts listening.
+    pub auto_migrate: bool,
+    pub cors: CorsConfig,
+    pub limits: LimitsConfig,
+    pub outbox: OutboxConfig,
+    pub webhooks: WebhookConfig,
+    pub event_stream: EventStreamConfig,
+    pub log_format: LogFormat,
+    pub otel: OtelConfig,
+    pub auth: AuthConfig,
+}
+
+#[derive(Clone, Debug)]
+pub struct DatabaseConfig {
+    pub url: String,
+    pub schema: String,
+    pub max_connections: u32,
+    pub min_connections: u32,
+    /// Time allowed for opening a single connection.
+    pub connect_timeout: Duration,
+    /// Time a query may wait for a free connection from the pool.
+    pub acquire_timeout: Duration,
+    /// Time after which an unused connection is closed.
+    pub idle_timeout: Duration,
+    /// Logs every SQL statement at debug level.
+    pub log_statements: bool,
+    /// Total time spent retrying the initial connection before giving up.
+    pub startup_timeout: Duration,
+}
+
+/// Cross-origin policy applied to every route.
+#[derive(Clone, Debug)]
+pub struct CorsConfig {
+    /// Origins allowed to call the API from a browser; `*` allows any.
+    pub origins: Vec<String>,
+    pub methods: Vec<String>,
+    /// Request headers browsers may send; `*` allows any.
+    pub headers: Vec<String>,
+    /// Lets browsers send cookies and auth headers; needs explicit origins.
+    pub allow_credentials: bool,
+    /// How long browsers may cache a preflight response.
+    pub max_age: Duration,
+}
+
+/// Protection against clients overloading the service.
+#[derive(Clone, Debug)]
+pub struct LimitsConfig {
+    /// Catalog reads allowed per client and minute; 0 disables the limit.
+    pub read_per_minute: u32,
+    /// Writes allowed per client and minute; 0 disables the limit.
+    pub write_per_minute: u32,
+    /// Failed authentications allowed per client address and minute; 0
+    /// disables the limit.
+    pub auth_failures_per_minute: u32,
+    /// Reverse proxies in front of the service that append to
+    /// `X-Forwarded-For`; the client address is the entry the outermost one
+    /// added. 0 uses the address of the connection.
+    pub trusted_proxy_hops: usize,
+    /// Largest request body accepted.
+    pub max_body_bytes: usize,
+    /// Bulk requests, like listing the whole catalog, running at once.
+    pub bulk_concurrency: usize,
+}
+
+/// Delivery of queued domain events to their sinks.
+#[derive(Clone, Debug)]
+pub struct OutboxConfig {
+    /// Time between two looks at the outbox for pending events.
+    pub poll_interval: Duration,
+    /// Largest number of events delivered per poll.
+    pub batch_size: u64,
+}
+
+/// Delivery of domain events to partner webhooks.
+#[derive(Clone, Debug)]
+pub struct WebhookConfig {
+    /// Time an endpoint gets to answer a delivery.
+    pub timeout: Duration,
+    /// Attempts after which a failing delivery is given up as dead.
+    pub max_attempts: i32,
+    /// Lets subscriptions target loopback, private and link-local addresses;
+    /// otherwise only public addresses are accepted and called.
+    pub allow_private_targets: bool,
+}
+
+/// Live inventory changes served on `GET /events/stream`.
+#[derive(Clone, Debug)]
+pub struct EventStreamConfig {
+    /// Latest changes kept for clients resuming with `Last-Event-ID`.
+    pub history: usize,
+    /// Time between two keep-alive comments on an idle stream.
+    pub keep_alive: Duration,
+}
+
+/// OpenTelemetry export, only used when built with the `otel` feature.
+#[derive(Clone, Debug)]
+pub struct OtelConfig {
+    /// OTLP/gRPC collector to send spans to; nothing is exported when unset.
+    pub endpoint: Option<String>,
+    pub service_name: String,
+}
+
+/// Keys and claims accepted on bearer tokens. Writes are rejected when no key
+/// is configured.
+#[derive(Clone, Debug, Default)]
+pub struct AuthConfig {
+    /// Shared secret for HS256 tokens.
+    pub jwt_secret: Option<String>,
+    /// Public keys for RS256 tokens, read from `JWT_JWKS_FILE`.
+    pub jwks: Option<JwkSet>,
+    /// Required `iss` claim, when set.
+    pub issuer: Option<String>,
+    /// Required `aud` claim, when set.
+    pub audience: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Every problem found while loading the configuration.
+#[derive(Debug)]
+pub struct ConfigError {
+    pub problems: Vec<String>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid configuration:")?;
+        for problem in &self.problems {
+            write!(f, "\n  - {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    pub fn bind_address(&self) -> SocketAddr {
+        SocketAddr::new(self.host, self.port)
+    }
+
+    /// Loads the configuration with the following precedence, highest first:
+    /// process environment, `.env`, the TOML file named by `CONFIG_FILE`
+    /// (or `config.toml` when present), built-in defaults.
+    pub fn load() -> Result<Self, ConfigError> {
+        // Variables already set in the environment win over the .env file
+        dotenv().ok();
+
+        let file = match env::var("CONFIG_FILE") {
+            Ok(path) => Some(read_config_file(&path)?),
+            Err(_) if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                Some(read_config_file(DEFAULT_CONFIG_FILE)?)
+            }
+            Err(_) => None,
+        };
+
+        Self::from_sources(file.as_deref(), |name| env::var(name).ok())
+    }
+
+    /// Builds the configuration from the contents of a TOML file and an
+    /// environment lookup, the latter taking precedence.
+    pub fn from_sources(
+        file: Option<&str>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ConfigError> {
+        let mut settings = Settings::default();
+
+        if let Some(contents) = file {
+            settings.read_file(contents);
+        }
+        for name in SETTINGS {
+            if let Some(value) = env(name) {
+                settings.values.insert(name, value);
+            }
+        }
+
+        let defaults = Config::default();
+        let config = Config {
+            host: settings.parse("HOST", defaults.host, "an IP address"),
+            port: settings.parse("PORT", defaults.port, "a port number"),
+            shutdown_timeout: settings.seconds("SHUTDOWN_TIMEOUT_SECS", defaults.shutdown_timeout),
+            database: DatabaseConfig {
+                url: settings.required("DATABASE_URL"),
+                schema: settings.string("DATABASE_SCHEMA", defaults.database.schema),
+                max_connections: settings.parse(
+                    "DATABASE_MAX_CONNECTIONS",
+                    defaults.database.max_connections,
+                    "a positive integer",
+                ),
+                min_connections: settings.parse(
+                    "DATABASE_MIN_CONNECTIONS",
+                    defaults.database.min_connections,
+                    "a positive integer",
+                ),
+                connect_timeout: settings.seconds(
+                    "DATABASE_CONNECT_TIMEOUT_SECS",
+                    defaults.database.connect_timeout,
+                ),
+                acquire_timeout: settings.seconds(
+                    "DATABASE_ACQUIRE_TIMEOUT_SECS",
+                    defaults.database.acquire_timeout,
+                ),
+                idle_timeout: settings.seconds(
+                    "DATABASE_IDLE_TIMEOUT_SECS",
+                    defaults.database.idle_timeout,
+                ),
+                log_statements: settings.parse(
+                    "DATABASE_LOG_STATEMENTS",
+                    defaults.database.log_statements,
+                    "`true` or `false`",
+                ),
+                startup_timeout: settings.seconds(
+                    "DATABASE_STARTUP_TIMEOUT_SECS",
+                    defaults.database.startup_timeout,
+                ),
+            },
+            auto_migrate: settings.parse("AUTO_MIGRATE", defaults.auto_migrate, "`true` or `false`"),
+            cors: CorsConfig {
+                origins: settings.list("CORS_ORIGINS", defaults.cors.origins),
+                methods: settings.list("CORS_METHODS", defaults.cors.methods),
+                headers: settings.list("CORS_HEADERS", defaults.cors.headers),
+                allow_credentials: settings.parse(
+                    "CORS_ALLOW_CREDENTIALS",
+                    defaults.cors.allow_credentials,
+                    "`true` or `false`",
+                ),
+                max_age: settings.seconds("CORS_MAX_AGE_SECS", defaults.cors.max_age),
+            },
+            limits: LimitsConfig {
+                read_per_minute: settings.parse(
+                    "RATE_LIMIT_READ_PER_MINUTE",
+                    defaults.limits.read_per_minute,
+                    "a number of requests",
+                ),
+                write_per_minute: settings.parse(
+                    "RATE_LIMIT_WRITE_PER_MINUTE",
+                    defaults.limits.write_per_minute,
+                    "a number of requests",
+                ),
+                auth_failures_per_minute: settings.parse(
+                    "AUTH_FAILURES_PER_MINUTE",
+                    defaults.limits.auth_failures_per_minute,
+                    "a number of attempts",
+                ),
+                trusted_proxy_hops: settings.parse(
+                    "TRUSTED_PROXY_HOPS",
+                    defaults.limits.trusted_proxy_hops,
+                    "a number of proxies",
+                ),
+                max_body_bytes: settings.parse(
+                    "MAX_BODY_BYTES",
+                    defaults.limits.max_body_bytes,
+                    "a number of bytes",
+                ),
+                bulk_concurrency: settings.parse(
+                    "BULK_CONCURRENCY_LIMIT",
+                    defaults.limits.bulk_concurrency,
+                    "a positive integer",
+                ),
+            },
+            outbox: OutboxConfig {
+                poll_interval: settings.seconds(
+                    "OUTBOX_POLL_INTERVAL_SECS",
+                    defaults.outbox.poll_interval,
+                ),
+                batch_size: settings.parse(
+                    "OUTBOX_BATCH_SIZE",
+                    defaults.outbox.batch_size,
+                    "a positive integer",
+                ),
+            },
+            webhooks: WebhookConfig {
+                timeout: settings.seconds("WEBHOOK_TIMEOUT_SECS", defaults.webhooks.timeout),
+                max_attempts: settings.parse(
+                    "WEBHOOK_MAX_ATTEMPTS",
+                    defaults.webhooks.max_attempts,
+                    "a positive integer",
+                ),
+                allow_private_targets: settings.parse(
+                    "WEBHOOK_ALLOW_PRIVATE_TARGETS",
+                    defaults.webhooks.allow_private_targets,
+                    "`true` or `false`",
+                ),
+            },
+            event_stream: EventStreamConfig {
+                history: settings.parse(
+                    "EVENT_STREAM_HISTORY",
+                    defaults.event_stream.history,
+                    "a positive integer",
+                ),
+                keep_alive: settings.seconds(
+                    "EVENT_STREAM_KEEP_ALIVE_SECS",
+                    defaults.event_stream.keep_alive,
+                ),
+            },
+            log_format: settings.parse("LOG_FORMAT", defaults.log_format, "`text` or `json`"),
+            otel: OtelConfig {
+                endpoint: settings.optional("OTEL_EXPORTER_OTLP_ENDPOINT"),
+                service_name: settings.string("OTEL_SERVICE_NAME", defaults.otel.service_name),
+            },
+            auth: AuthConfig {
+                jwt_secret: settings.optional("JWT_SECRET"),
+                jwks: settings.jwks("JWT_JWKS_FILE"),
+                issuer: settings.optional("JWT_ISSUER"),
+                audience: settings.optional("JWT_AUDIENCE"),
+            },
+        };
+
+        if config.database.max_connections == 0 {
+            settings
+                .problems
+                .push("DATABASE_MAX_CONNECTIONS: must be greater than 0".to_string());
+        } else if config.database.min_connections > config.database.max_connections {
+            settings.problems.push(
+                "DATABASE_MIN_CONNECTIONS: must not exceed DATABASE_MAX_CONNECTIONS".to_string(),
+            );
+        }
+
+        settings.problems.extend(config.cors.problems());
+        if config.limits.bulk_concurrency == 0 {
+            settings
+                .problems
+                .push("BULK_CONCURRENCY_LIMIT: must be greater than 0".to_string());
+        }
+        if config.outbox.poll_interval.is_zero() {
+            settings
+                .problems
+                .push("OUTBOX_POLL_INTERVAL_SECS: must be greater than 0".to_string());
+        }
+        if config.outbox.batch_size == 0 {
+            settings
+                .problems
+                .push("OUTBOX_BATCH_SIZE: must be greater than 0".to_string());
+        }
+        if config.webhooks.timeout.is_zero() {
+            settings
+                .problems
+                .push("WEBHOOK_TIMEOUT_SECS: must be greater than 0".to_string());
+        }
+        if config.webhooks.max_attempts <= 0 {
+            settings
+                .problems
+                .push("WEBHOOK_MAX_ATTEMPTS: must be greater than 0".to_string());
+        }
+        if config.event_stream.history == 0 {
+            settings
+                .problems
+                .push("EVENT_STREAM_HISTORY: must be greater than 0".to_string());
+        }
+        if config.event_stream.keep_alive.is_zero() {
+            settings
+                .problems
+                .push("EVENT_STREAM_KEEP_ALIVE_SECS: must be greater than 0".to_string());
+        }
+
+        if settings.problems.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError {
+                problems: settings.problems,
+            })
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            host: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            port: 8080,
+            shutdown_timeout: Duration::from_secs(30),
+            database: DatabaseConfig {
+                url: String::new(),
+                schema: "public".to_string(),
+                max_connections: 10,
+                min_connections: 1,
+                connect_timeout: Duration::from_secs(5),
+                acquire_timeout: Duration::from_secs(5),
+                idle_timeout: Duration::from_secs(600),
+                log_statements: false,
+                startup_timeout: Duration::from_secs(60),
+            },
+            auto_migrate: false,
+            cors: CorsConfig {
+                origins: vec!["*".to_string()],
+                methods: ["GET", "POST", "PUT", "PATCH", "DELETE"]
+                    .map(str::to_string)
+                    .to_vec(),
+                headers: [
+                    "authorization",
+                    "content-type",
+                    "idempotency-key",
+                    "last-event-id",
+                    "x-api-key",
+                    "x-request-id",
+                ]
+                    .map(str::to_string)
+                    .to_vec(),
+                allow_credentials: false,
+                max_age: Duration::from_secs(600),
+            },
+            limits: LimitsConfig {
+                read_per_minute: 600,
+                write_per_minute: 60,
+                auth_failures_per_minute: 10,
+                trusted_proxy_hops: 0,
+                max_body_bytes: 1024 * 1024,
+                bulk_concurrency: 8,
+            },
+            outbox: OutboxConfig {
+                poll_interval: Duration::from_secs(1),
+                batch_size: 100,
+            },
+            webhooks: WebhookConfig {
+                timeout: Duration::from_secs(10),
+                max_attempts: 8,
+                allow_private_targets: false,
+            },
+            event_stream: EventStreamConfig {
+                history: 1000,
+                keep_alive: Duration::from_secs(15),
+            },
+            log_format: LogFormat::Text,
+            otel: OtelConfig {
+                endpoint: None,
+                service_name: env!("CARGO_PKG_NAME").to_string(),
+            },
+            auth: AuthConfig::default(),
+        }
+    }
+}
+
+impl CorsConfig {
+    fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        for origin in self.origins.iter().filter(|origin| *origin != "*") {
+            let valid = (origin.starts_with("http://") || origin.starts_with("https://"))
+                && HeaderValue::from_str(origin).is_ok();
+            if !valid {
+                problems.push(format!("CORS_ORIGINS: `{}` is not an origin", origin));
+            }
+        }
+        for method in &self.methods {
+            if Method::from_bytes(method.as_bytes()).is_err() {
+                problems.push(format!("CORS_METHODS: `{}` is not a method", method));
+            }
+        }
+        for name in self.headers.iter().filter(|name| *name != "*") {
+            if HeaderName::from_bytes(name.as_bytes()).is_err() {
+                problems.push(format!("CORS_HEADERS: `{}` is not a header name", name));
+            }
+        }
+        // Browsers refuse credentialed responses that allow any origin
+        let any_allowed = self.origins.iter().chain(&self.headers).any(|value| value == "*");
+        if self.allow_credentials && any_allowed {
+            problems.push(
+                "CORS_ALLOW_CREDENTIALS: needs explicit CORS_ORIGINS and CORS_HEADERS".to_string(),
+            );
+        }
+
+        problems
+    }
+}
+
+fn read_config_file(path: &str) -> Result<String, ConfigError> {
+    fs::read_to_string(path).map_err(|err| ConfigError {
+        problems: vec![format!("CONFIG_FILE: cannot read `{}`: {}", path, err)],
+    })
+}
+
+/// Raw setting values, collected from every source before being parsed.
+#[derive(Default)]
+struct Settings {
+    values: HashMap<&'static str, String>,
+    problems: Vec<String>,
+}
+
+impl Settings {
+    fn read_file(&mut self, contents: &str) {
+        let table = match contents.parse::<toml::Table>() {
+            Ok(table) => table,
+            Err(err) => {
+                self.problems
+                    .push(format!("config file: invalid TOML: {}", err.message()));
+                return;
+            }
+        };
+
+        for (key, value) in table {
+            let name = match SETTINGS
+                .iter()
+                .find(|name| name.eq_ignore_ascii_case(&key))
+            {
+                Some(name) => *name,
+                None => {
+                    self.problems
+                        .push(format!("config file: unknown setting `{}`", key));
+                    continue;
+                }
+            };
+
+            let value = match value {
+                toml::Value::String(value) => value,
+                toml::Value::Array(values) => values
+                    .iter()
+                    .map(|value| match value {
+                        toml::Value::String(value) => value.clone(),
+                        other => other.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(","),
+                other => other.to_string(),
+            };
+            self.values.insert(name, value);
+        }
+    }
+
+    fn required(&mut self, name: &str) -> String {
+        match self.values.get(name) {
+            Some(value) if !value.trim().is_empty() => value.clone(),
+            _ => {
+                self.problems.push(format!("{}: missing", name));
+                String::new()
+            }
+        }
+    }
+
+    fn optional(&self, name: &str) -> Option<String> {
+        self.values
+            .get(name)
+            .filter(|value| !value.trim().is_empty())
+            .cloned()
+    }
+
+    fn string(&self, name: &str, default: String) -> String {
+        self.values.get(name).cloned().unwrap_or(default)
+    }
+
+    fn list(&self, name: &str, default: Vec<String>) -> Vec<String> {
+        match self.values.get(name) {
+            Some(value) => value
+                .split(',')
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+                .collect(),
+            None => default,
+        }
+    }
+
+    fn jwks(&mut self, name: &str) -> Option<JwkSet> {
+        let path = self.optional(name)?;
+        let parsed = fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| serde_json::from_str(&contents).map_err(|err| err.to_string()));
+
+        match parsed {
+            Ok(jwks) => Some(jwks),
+            Err(err) => {
+                self.problems
+                    .push(format!("{}: cannot load `{}`: {}", name, path, err));
+                None
+            }
+        }
+    }
+
+    fn seconds(&mut self, name: &str, default: Duration) -> Duration {
+        Duration::from_secs(self.parse(name, default.as_secs(), "a number of seconds"))
+    }
+
+    fn parse<T: FromStr>(&mut self, name: &str, default: T, expected: &str) -> T {
+        match self.values.get(name) {
+            Some(value) => match value.trim().parse() {
+                Ok(parsed) => parsed,
+                Err(_) => {
+                    self.problems.push(format!(
+                        "{}: expected {}, got `{}`",
+                        name, expected, value
+                    ));
+                    default
+                }
+            },
+            None => default,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_from(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let pairs: HashMap<String, String> = pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name| pairs.get(name).cloned()
+    }
+
+    #[test]
+    fn environment_overrides_file() {
+        let file = r#"
+            database_url = "postgres://file/db"
+            port = 9000
+            cors_origins = ["https://admin.example.com", "https://shop.example.com"]
+        "#;
+
+        let config = Config::from_sources(
+            Some(file),
+            env_from(&[("PORT", "9100"), ("LOG_FORMAT", "json")]),
+        )
+        .unwrap();
+
+        assert_eq!(config.database.url, "postgres://file/db");
+        assert_eq!(config.port, 9100);
+        assert_eq!(config.log_format, LogFormat::Json);
+        assert_eq!(config.cors.origins.len(), 2);
+        assert_eq!(config.database.schema, "public");
+    }
+
+    #[test]
+    fn every_problem_is_reported() {
+        let error = Config::from_sources(
+            Some("prot = 8080"),
+            env_from(&[("PORT", "eighty"), ("DATABASE_MAX_CONNECTIONS", "-1")]),
+        )
+        .unwrap_err();
+
+        assert_eq!(error.problems.len(), 4);
+        let message = error.to_string();
+        assert!(message.contains("unknown setting `prot`"));
+        assert!(message.contains("PORT: expected a port number, got `eighty`"));
+        assert!(message.contains("DATABASE_MAX_CONNECTIONS"));
+        assert!(message.contains("DATABASE_URL: missing"));
+    }
+
+    #[test]
+    fn min_connections_cannot_exceed_max() {
+        let error = Config::from_sources(
+            None,
+            env_from(&[
+                ("DATABASE_URL", "postgres://localhost/db"),
+                ("DATABASE_MAX_CONNECTIONS", "2"),
+                ("DATABASE_MIN_CONNECTIONS", "5"),
+            ]),
+        )
+        .unwrap_err();
+
+        assert_eq!(error.problems.len(), 1);
+    }
+
+    #[test]
+    fn credentials_need_explicit_origins() {
+        let error = Config::from_sources(
+            None,
+            env_from(&[
+                ("DATABASE_URL", "postgres://localhost/db"),
+                ("CORS_ALLOW_CREDENTIALS", "true"),
+                ("CORS_METHODS", "GET,FETCH ME"),
+            ]),
+        )
+        .unwrap_err();
+
+        assert_eq!(error.problems.len(), 2);
+        assert!(error.problems[0].starts_with("CORS_METHODS"));
+        assert!(error.problems[1].starts_with("CORS_ALLOW_CREDENTIALS"));
+    }
+
+    #[test]
+    fn unreadable_jwks_file_is_reported() {
+        let error = Config::from_sources(
+            None,
+            env_from(&[
+                ("DATABASE_URL", "postgres://localhost/db"),
+                ("JWT_JWKS_FILE", "/nonexistent/jwks.json"),
+            ]),
+        )
+        .unwrap_err();
+
+        assert!(error.problems[0].starts_with("JWT_JWKS_FILE: cannot load"));
+    }
+}
@@ -3,7 +3,7 @@
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
-#[sea_orm(schema_name = "public", table_name = "item")]
+#[sea_orm(table_name = "item")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
@@ -2,5 +2,11 @@
 
 pub mod prelude;
 
+pub mod api_key;
+pub mod audit_log;
+pub mod idempotency_key;
 pub mod item;
+pub mod outbox;
 pub mod product;
+pub mod webhook_delivery;
+pub mod webhook_subscription;
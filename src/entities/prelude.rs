@@ -1,4 +1,10 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.2
 
+pub use super::api_key::Entity as ApiKey;
+pub use super::audit_log::Entity as AuditLog;
+pub use super::idempotency_key::Entity as IdempotencyKey;
 pub use super::item::Entity as Item;
+pub use super::outbox::Entity as Outbox;
 pub use super::product::Entity as Product;
+pub use super::webhook_delivery::Entity as WebhookDelivery;
+pub use super::webhook_subscription::Entity as WebhookSubscription;
@@ -3,7 +3,7 @@
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
-#[sea_orm(schema_name = "public", table_name = "product")]
+#[sea_orm(table_name = "product")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
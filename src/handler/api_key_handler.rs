@@ -0,0 +1,152 @@
+use axum::{extract::{Path, State}, http::StatusCode, response::IntoResponse, Json};
+use tracing::{error, info, instrument};
+
+use crate::{
+    middleware::auth::Caller,
+    models::{
+        api_key_model::{ApiKeyModel, CreateApiKeyModel, IssuedApiKeyModel},
+        ErrorBodyModel, ErrorModel, NotFoundErrorModel,
+    },
+    services::api_key_service::ApiKeyService,
+};
+
+#[utoipa::path(
+    post,
+    path = "/api-keys",
+    tag = "api-keys",
+    request_body = CreateApiKeyModel,
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 201, description = "Key created; `key` is not shown again", body = IssuedApiKeyModel),
+        (status = 400, description = "Invalid name, scopes or expiry", body = ErrorBodyModel),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks api-keys:manage", body = ErrorBodyModel),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service, caller, request))]
+pub async fn create_api_key(
+    State(service): State<ApiKeyService>,
+    caller: Caller,
+    Json(request): Json<CreateApiKeyModel>,
+) -> impl IntoResponse {
+    match service.create_api_key(request).await {
+        Ok(api_key) => {
+            info!(subject = %caller.subject, "API key {} created", api_key.api_key.id);
+            Ok((StatusCode::CREATED, Json(api_key)))
+        }
+        Err(ErrorModel::ValidationError(msg)) => {
+            error!("Failed to create API key: {}", msg);
+            Err((StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))))
+        }
+        Err(ErrorModel::DatabaseError(msg)) => {
+            error!("Failed to create API key: {}", msg);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": msg}))))
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api-keys",
+    tag = "api-keys",
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 200, description = "Every key, without secrets", body = [ApiKeyModel]),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks api-keys:manage", body = ErrorBodyModel),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service))]
+pub async fn get_all_api_keys(State(service): State<ApiKeyService>) -> impl IntoResponse {
+    match service.get_all_api_keys().await {
+        Ok(api_keys) => Ok((StatusCode::OK, Json(api_keys))),
+        Err(ErrorModel::ValidationError(msg)) | Err(ErrorModel::DatabaseError(msg)) => {
+            error!("Failed to fetch API keys: {}", msg);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": msg}))))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api-keys/{id}/rotate",
+    tag = "api-keys",
+    params(("id" = i32, Path, description = "API key ID")),
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 200, description = "New secret issued; the previous one stops working", body = IssuedApiKeyModel),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks api-keys:manage", body = ErrorBodyModel),
+        (status = 404, description = "API key not found", body = ErrorBodyModel),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service, caller))]
+pub async fn rotate_api_key(
+    State(service): State<ApiKeyService>,
+    caller: Caller,
+    Path(api_key_id): Path<i32>,
+) -> impl IntoResponse {
+    not_found_response(
+        service.rotate_api_key(api_key_id).await,
+        &caller,
+        "rotated",
+        api_key_id,
+    )
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api-keys/{id}",
+    tag = "api-keys",
+    params(("id" = i32, Path, description = "API key ID")),
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 200, description = "Key revoked", body = ApiKeyModel),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks api-keys:manage", body = ErrorBodyModel),
+        (status = 404, description = "API key not found", body = ErrorBodyModel),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service, caller))]
+pub async fn revoke_api_key(
+    State(service): State<ApiKeyService>,
+    caller: Caller,
+    Path(api_key_id): Path<i32>,
+) -> impl IntoResponse {
+    not_found_response(
+        service.revoke_api_key(api_key_id).await,
+        &caller,
+        "revoked",
+        api_key_id,
+    )
+}
+
+fn not_found_response<T: serde::Serialize>(
+    result: Result<T, NotFoundErrorModel>,
+    caller: &Caller,
+    action: &str,
+    api_key_id: i32,
+) -> Result<(StatusCode, Json<T>), (StatusCode, Json<serde_json::Value>)> {
+    match result {
+        Ok(api_key) => {
+            info!(subject = %caller.subject, "API key {} {}", api_key_id, action);
+            Ok((StatusCode::OK, Json(api_key)))
+        }
+        Err(NotFoundErrorModel::ValidationError(msg)) => {
+            error!("API key validation failed: {}", msg);
+            Err((StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))))
+        }
+        Err(NotFoundErrorModel::NotFoundError(msg)) => {
+            error!("API key {} not found", api_key_id);
+            Err((StatusCode::NOT_FOUND, Json(serde_json::json!({"error": msg}))))
+        }
+        Err(NotFoundErrorModel::DatabaseError(msg)) => {
+            error!("Database error on API key {}: {}", api_key_id, msg);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({"error": msg}))))
+        }
+    }
+}
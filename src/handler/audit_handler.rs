@@ -0,0 +1,53 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use tracing::{error, instrument};
+
+use crate::{
+    models::{
+        audit_model::{AuditLogModel, AuditQueryModel},
+        ErrorBodyModel, ErrorModel,
+    },
+    services::audit_service::AuditService,
+};
+
+#[utoipa::path(
+    get,
+    path = "/audit",
+    tag = "audit",
+    params(AuditQueryModel),
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 200, description = "Matching changes, newest first", body = [AuditLogModel]),
+        (status = 400, description = "Invalid filter", body = ErrorBodyModel),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks audit:read", body = ErrorBodyModel),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service))]
+pub async fn get_audit_logs(
+    State(service): State<AuditService>,
+    Query(query): Query<AuditQueryModel>,
+) -> impl IntoResponse {
+    match service.get_audit_logs(query).await {
+        Ok(entries) => Ok((StatusCode::OK, Json(entries))),
+        Err(ErrorModel::ValidationError(msg)) => {
+            error!("Invalid audit log query: {}", msg);
+            Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": msg})),
+            ))
+        }
+        Err(ErrorModel::DatabaseError(msg)) => {
+            error!("Failed to fetch audit log: {}", msg);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": msg})),
+            ))
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use axum::response::{Html, IntoResponse};
+
+// Default route handler function
+pub async fn default_handler() -> impl IntoResponse {
+    let response = r#"
+📋 Welcome to the Product-Item Microservice<br><br>
+The API is documented at <a href="/docs">/docs</a>
+(OpenAPI document: <a href="/openapi.json">/openapi.json</a>).<br>
+"#;
+    Html(response)
+}
@@ -0,0 +1,88 @@
+use std::convert::Infallible;
+
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, HeaderName},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+};
+use futures::{future, stream, StreamExt};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{instrument, warn};
+
+use crate::{
+    models::{
+        event_model::{EventStreamQueryModel, InventoryChangeModel, LiveEventModel},
+        ErrorBodyModel,
+    },
+    services::event_stream_service::EventStreamService,
+};
+
+/// Sent by reconnecting `EventSource`s with the ID of the last event they got.
+pub static LAST_EVENT_ID: HeaderName = HeaderName::from_static("last-event-id");
+
+#[utoipa::path(
+    get,
+    path = "/events/stream",
+    tag = "events",
+    params(
+        EventStreamQueryModel,
+        ("Last-Event-ID" = Option<u64>, Header, description = "Replays the kept changes published after this event"),
+    ),
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 200, description = "Server-Sent Events: `id` orders the changes, `event` is their type and `data` their JSON fields", content_type = "text/event-stream", body = InventoryChangeModel),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks inventory:read", body = ErrorBodyModel),
+        (status = 429, description = "Too many requests", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service, headers))]
+pub async fn stream_events(
+    State(service): State<EventStreamService>,
+    Query(query): Query<EventStreamQueryModel>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let last_event_id = headers
+        .get(&LAST_EVENT_ID)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    let (missed, receiver) = service.subscribe(last_event_id);
+
+    let live = stream::unfold(receiver, |mut receiver| async move {
+        match receiver.recv().await {
+            Ok(event) => Some((event, receiver)),
+            // Ending the stream makes the client reconnect and catch up from
+            // the kept history with Last-Event-ID
+            Err(RecvError::Lagged(skipped)) => {
+                warn!("Event stream client fell {} events behind", skipped);
+                None
+            }
+            Err(RecvError::Closed) => None,
+        }
+    });
+
+    // Ends with the server, so graceful shutdown does not wait on open streams
+    let events = stream::iter(missed)
+        .chain(live)
+        .take_until(service.closed())
+        .filter(move |event| {
+            future::ready(
+                query
+                    .product_id
+                    .is_none_or(|id| event.change.product_id() == id),
+            )
+        })
+        .map(|event| Ok::<_, Infallible>(to_sse_event(event)));
+
+    Sse::new(events).keep_alive(KeepAlive::new().interval(service.keep_alive()))
+}
+
+fn to_sse_event(event: LiveEventModel) -> Event {
+    Event::default()
+        .id(event.id.to_string())
+        .event(event.change.event_type())
+        .data(event.change.payload().to_string())
+}
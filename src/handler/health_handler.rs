@@ -0,0 +1,47 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use tracing::warn;
+
+use crate::{
+    models::health_model::{HealthModel, ReadinessModel, VersionModel},
+    services::health_service::HealthService,
+};
+
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    tag = "operations",
+    responses((status = 200, description = "The process is running", body = HealthModel))
+)]
+pub async fn liveness(State(service): State<HealthService>) -> impl IntoResponse {
+    (StatusCode::OK, Json(service.liveness()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "operations",
+    responses(
+        (status = 200, description = "Database reachable and schema up to date", body = ReadinessModel),
+        (status = 503, description = "At least one check failed", body = ReadinessModel),
+    )
+)]
+pub async fn readiness(State(service): State<HealthService>) -> impl IntoResponse {
+    let readiness = service.readiness().await;
+
+    if readiness.is_ready() {
+        (StatusCode::OK, Json(readiness))
+    } else {
+        warn!("Readiness check failed");
+        (StatusCode::SERVICE_UNAVAILABLE, Json(readiness))
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/version",
+    tag = "operations",
+    responses((status = 200, description = "Build information", body = VersionModel))
+)]
+pub async fn version(State(service): State<HealthService>) -> impl IntoResponse {
+    (StatusCode::OK, Json(service.version()))
+}
@@ -1,15 +1,34 @@
 use axum::{extract::{Path, State}, http:: StatusCode, response::{IntoResponse, Json}};
-use tracing::{error, info};
+use tracing::{error, info, instrument};
 
-use crate::{models::{item_model::{ CreateItemModel,UpdateItemModel}, ErrorModel, NotFoundErrorModel}, services::item_service::ItemService};
+use crate::{models::{item_model::{CreateItemModel, ItemModel, UpdateItemModel}, ErrorBodyModel, ErrorModel, NotFoundErrorModel}, middleware::auth::Actor, services::item_service::ItemService};
 
+#[utoipa::path(
+    post,
+    path = "/item",
+    tag = "items",
+    params(("Idempotency-Key" = Option<String>, Header, description = "Replays the first response to retries of this request")),
+    request_body = CreateItemModel,
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 201, description = "Item created", body = ItemModel),
+        (status = 400, description = "Invalid item", body = ErrorBodyModel),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks the required permission", body = ErrorBodyModel),
+        (status = 409, description = "A request with the same Idempotency-Key is still running", body = ErrorBodyModel),
+        (status = 422, description = "Idempotency-Key was used for a different request", body = ErrorBodyModel),
+        (status = 500, description = "Database error, e.g. unknown product", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service, actor, item_data), fields(product_id = item_data.product_id))]
 pub async fn create_item(
     State(service): State<ItemService>,
+    actor: Actor,
     Json(item_data): Json<CreateItemModel>,
 )-> impl IntoResponse{
-    match service.create_item(item_data).await {
+    match service.create_item(item_data, &actor).await {
         Ok(item) => {
-            info!("Item created successfully");
+            info!(subject = %actor.actor, "Item created successfully");
             Ok((StatusCode::CREATED,Json(item)))
         }
         Err(ErrorModel::ValidationError(msg)) => {
@@ -26,13 +45,29 @@ pub async fn create_item(
 }
 
 
+#[utoipa::path(
+    delete,
+    path = "/item/{id}",
+    tag = "items",
+    params(("id" = i32, Path, description = "Item ID")),
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 200, description = "Item deleted", body = String),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks the required permission", body = ErrorBodyModel),
+        (status = 404, description = "Item not found", body = ErrorBodyModel),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service, actor))]
 pub async fn delete_item(
     State(service): State<ItemService>,
+    actor: Actor,
     Path(item_id): Path<i32>,
 )-> impl IntoResponse {
-    match service.delete_item(item_id).await {
+    match service.delete_item(item_id, &actor).await {
         Ok(_) => {
-            info!("Item deleted successfully");
+            info!(subject = %actor.actor, "Item deleted successfully");
             Ok((StatusCode::OK,Json("Item deleted")))
         }
         Err(NotFoundErrorModel::ValidationError(msg)) => {
@@ -53,14 +88,36 @@ pub async fn delete_item(
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/item/{id}",
+    tag = "items",
+    params(
+        ("id" = i32, Path, description = "Item ID"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Replays the first response to retries of this request"),
+    ),
+    request_body = UpdateItemModel,
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 202, description = "Item updated", body = ItemModel),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks the required permission", body = ErrorBodyModel),
+        (status = 404, description = "Item not found", body = ErrorBodyModel),
+        (status = 409, description = "A request with the same Idempotency-Key is still running", body = ErrorBodyModel),
+        (status = 422, description = "Idempotency-Key was used for a different request", body = ErrorBodyModel),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service, actor, item_data))]
 pub async fn update_item(
     State(service): State<ItemService>,
+    actor: Actor,
     Path(item_id): Path<i32>,
     Json(item_data): Json<UpdateItemModel>,
 )->impl IntoResponse{
-    match service.update_item(item_id,item_data).await {
+    match service.update_item(item_id,item_data, &actor).await {
         Ok(item) => {
-            info!("Item with ID {} updated successfully", item_id);
+            info!(subject = %actor.actor, "Item with ID {} updated successfully", item_id);
             Ok((StatusCode::ACCEPTED,Json(item)))
         }
         Err(NotFoundErrorModel::ValidationError(msg)) => {
@@ -87,6 +144,18 @@ pub async fn update_item(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/item/{id}",
+    tag = "items",
+    params(("id" = i32, Path, description = "Item ID")),
+    responses(
+        (status = 200, description = "The item", body = ItemModel),
+        (status = 404, description = "Item not found", body = ErrorBodyModel),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service))]
 pub async fn get_item_by_id(
     State(service): State<ItemService>,
     Path(item_id): Path<i32>,                       
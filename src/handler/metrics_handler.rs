@@ -0,0 +1,19 @@
+use axum::{extract::State, http::{header, StatusCode}, response::IntoResponse};
+
+use crate::services::metrics_service::MetricsService;
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "operations",
+    responses(
+        (status = 200, description = "Prometheus text exposition format", body = String, content_type = "text/plain"),
+    )
+)]
+pub async fn get_metrics(State(service): State<MetricsService>) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        service.render().await,
+    )
+}
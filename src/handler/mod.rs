@@ -1,2 +1,9 @@
+pub mod api_key_handler;
+pub mod audit_handler;
+pub mod default_handler;
+pub mod event_handler;
+pub mod health_handler;
+pub mod metrics_handler;
 pub mod product_handler;
-pub mod item_handler;
\ No newline at end of file
+pub mod item_handler;
+pub mod webhook_handler;
@@ -1,17 +1,36 @@
 use axum::{extract::{Path, State}, http::StatusCode, response::IntoResponse,Json};
-use tracing::{info,error};
+use tracing::{info,error,instrument};
 
-use crate::{models::{product_model::{CreateProductModal, UpdateProductModal}, ErrorModel, NotFoundErrorModel}, services::product_service::ProductService};
+use crate::{models::{product_model::{CreateProductModal, ProductItemModel, UpdateProductModal, WholeProductModel}, ErrorBodyModel, ErrorModel, NotFoundErrorModel}, middleware::auth::Actor, services::product_service::ProductService};
 
 
+#[utoipa::path(
+    post,
+    path = "/product",
+    tag = "products",
+    params(("Idempotency-Key" = Option<String>, Header, description = "Replays the first response to retries of this request")),
+    request_body = CreateProductModal,
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 201, description = "Product created together with its items", body = ProductItemModel),
+        (status = 400, description = "Invalid product or item", body = ErrorBodyModel),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks the required permission", body = ErrorBodyModel),
+        (status = 409, description = "A request with the same Idempotency-Key is still running", body = ErrorBodyModel),
+        (status = 422, description = "Idempotency-Key was used for a different request", body = ErrorBodyModel),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service, actor, product_data))]
 pub async fn create_product(
     State(service):State<ProductService>,
+    actor: Actor,
     Json(product_data): Json<CreateProductModal>,
 ) -> impl IntoResponse{
     
-    match service.create_product(product_data).await {
+    match service.create_product(product_data, &actor).await {
         Ok(product) => {
-            info!("Product created successfully");
+            info!(subject = %actor.actor, "Product created successfully");
             Ok((StatusCode::CREATED,Json(product)))
         }
         Err(ErrorModel::ValidationError(msg)) => {
@@ -28,6 +47,16 @@ pub async fn create_product(
 }
 
 
+#[utoipa::path(
+    get,
+    path = "/product",
+    tag = "products",
+    responses(
+        (status = 200, description = "Every product with its items", body = [ProductItemModel]),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service))]
 pub async fn get_all_products(
     State(service): State<ProductService>,
 ) -> impl IntoResponse {
@@ -52,14 +81,31 @@ pub async fn get_all_products(
 
 }
 
+#[utoipa::path(
+    put,
+    path = "/product/{id}",
+    tag = "products",
+    params(("id" = i32, Path, description = "Product ID")),
+    request_body = UpdateProductModal,
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 202, description = "Product updated", body = WholeProductModel),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks the required permission", body = ErrorBodyModel),
+        (status = 404, description = "Product not found", body = ErrorBodyModel),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service, actor, product_data))]
 pub async fn update_product(
     State(service): State<ProductService>,
+    actor: Actor,
     Path(product_id): Path<i32>,
     Json(product_data): Json<UpdateProductModal>,
 ) -> impl IntoResponse {
-    match service.update_product(product_id, product_data).await {
+    match service.update_product(product_id, product_data, &actor).await {
         Ok(product) => {
-            info!("Product with ID {} updated successfully", product_id);
+            info!(subject = %actor.actor, "Product with ID {} updated successfully", product_id);
             Ok((StatusCode::ACCEPTED, Json(product)))
         }
         Err(NotFoundErrorModel::ValidationError(msg)) => {
@@ -87,13 +133,29 @@ pub async fn update_product(
 }
 
 
+#[utoipa::path(
+    delete,
+    path = "/product/{id}",
+    tag = "products",
+    params(("id" = i32, Path, description = "Product ID")),
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 200, description = "Product deleted", body = String),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks the required permission", body = ErrorBodyModel),
+        (status = 404, description = "Product not found", body = ErrorBodyModel),
+        (status = 500, description = "Database error, e.g. the product still has items", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service, actor))]
 pub async fn delete_product(
     State(service): State<ProductService>,
+    actor: Actor,
     Path(product_id): Path<i32>,
 )-> impl IntoResponse{
-    match service.delete_product(product_id).await {
+    match service.delete_product(product_id, &actor).await {
         Ok(_) => {
-            info!("Product with ID {} deleted successfully", product_id);
+            info!(subject = %actor.actor, "Product with ID {} deleted successfully", product_id);
             Ok((StatusCode::OK, Json("Product deleted")))
         }
         Err(NotFoundErrorModel::ValidationError(msg)) => {
@@ -0,0 +1,218 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use tracing::{error, info, instrument};
+
+use crate::{
+    middleware::auth::Caller,
+    models::{
+        webhook_model::{
+            CreateWebhookModel, IssuedWebhookModel, UpdateWebhookModel, WebhookDeliveryModel,
+            WebhookModel,
+        },
+        ErrorBodyModel, ErrorModel, NotFoundErrorModel,
+    },
+    services::webhook_service::WebhookService,
+};
+
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    tag = "webhooks",
+    request_body = CreateWebhookModel,
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 201, description = "Subscription created; `secret` is not shown again", body = IssuedWebhookModel),
+        (status = 400, description = "Invalid URL, event types or secret", body = ErrorBodyModel),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks webhooks:manage", body = ErrorBodyModel),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service, caller, request))]
+pub async fn create_webhook(
+    State(service): State<WebhookService>,
+    caller: Caller,
+    Json(request): Json<CreateWebhookModel>,
+) -> impl IntoResponse {
+    match service.create_webhook(request).await {
+        Ok(webhook) => {
+            info!(subject = %caller.subject, "Webhook {} created", webhook.webhook.id);
+            Ok((StatusCode::CREATED, Json(webhook)))
+        }
+        Err(ErrorModel::ValidationError(msg)) => {
+            error!("Failed to create webhook: {}", msg);
+            Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": msg})),
+            ))
+        }
+        Err(ErrorModel::DatabaseError(msg)) => {
+            error!("Failed to create webhook: {}", msg);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": msg})),
+            ))
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    tag = "webhooks",
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 200, description = "Every subscription, without secrets", body = [WebhookModel]),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks webhooks:manage", body = ErrorBodyModel),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service))]
+pub async fn get_all_webhooks(State(service): State<WebhookService>) -> impl IntoResponse {
+    match service.get_all_webhooks().await {
+        Ok(webhooks) => Ok((StatusCode::OK, Json(webhooks))),
+        Err(ErrorModel::ValidationError(msg)) | Err(ErrorModel::DatabaseError(msg)) => {
+            error!("Failed to fetch webhooks: {}", msg);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": msg})),
+            ))
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = i32, Path, description = "Webhook ID")),
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 200, description = "Subscription found", body = WebhookModel),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks webhooks:manage", body = ErrorBodyModel),
+        (status = 404, description = "Webhook not found", body = ErrorBodyModel),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service))]
+pub async fn get_webhook_by_id(
+    State(service): State<WebhookService>,
+    Path(webhook_id): Path<i32>,
+) -> impl IntoResponse {
+    not_found_response(service.get_webhook_by_id(webhook_id).await, webhook_id)
+}
+
+#[utoipa::path(
+    put,
+    path = "/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = i32, Path, description = "Webhook ID")),
+    request_body = UpdateWebhookModel,
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 200, description = "Subscription updated", body = WebhookModel),
+        (status = 400, description = "Invalid URL, event types or secret", body = ErrorBodyModel),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks webhooks:manage", body = ErrorBodyModel),
+        (status = 404, description = "Webhook not found", body = ErrorBodyModel),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service, caller, webhook_data))]
+pub async fn update_webhook(
+    State(service): State<WebhookService>,
+    caller: Caller,
+    Path(webhook_id): Path<i32>,
+    Json(webhook_data): Json<UpdateWebhookModel>,
+) -> impl IntoResponse {
+    let result = service.update_webhook(webhook_id, webhook_data).await;
+    if result.is_ok() {
+        info!(subject = %caller.subject, "Webhook {} updated", webhook_id);
+    }
+    not_found_response(result, webhook_id)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = i32, Path, description = "Webhook ID")),
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 200, description = "Subscription and its deliveries deleted", body = bool),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks webhooks:manage", body = ErrorBodyModel),
+        (status = 404, description = "Webhook not found", body = ErrorBodyModel),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service, caller))]
+pub async fn delete_webhook(
+    State(service): State<WebhookService>,
+    caller: Caller,
+    Path(webhook_id): Path<i32>,
+) -> impl IntoResponse {
+    let result = service.delete_webhook(webhook_id).await;
+    if result.is_ok() {
+        info!(subject = %caller.subject, "Webhook {} deleted", webhook_id);
+    }
+    not_found_response(result, webhook_id)
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks/{id}/deliveries",
+    tag = "webhooks",
+    params(("id" = i32, Path, description = "Webhook ID")),
+    security(("bearer" = []), ("api_key" = [])),
+    responses(
+        (status = 200, description = "The latest 100 deliveries, newest first", body = [WebhookDeliveryModel]),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorBodyModel),
+        (status = 403, description = "Caller lacks webhooks:manage", body = ErrorBodyModel),
+        (status = 404, description = "Webhook not found", body = ErrorBodyModel),
+        (status = 500, description = "Database error", body = ErrorBodyModel),
+    )
+)]
+#[instrument(skip(service))]
+pub async fn get_webhook_deliveries(
+    State(service): State<WebhookService>,
+    Path(webhook_id): Path<i32>,
+) -> impl IntoResponse {
+    not_found_response(service.get_webhook_deliveries(webhook_id).await, webhook_id)
+}
+
+fn not_found_response<T: serde::Serialize>(
+    result: Result<T, NotFoundErrorModel>,
+    webhook_id: i32,
+) -> Result<(StatusCode, Json<T>), (StatusCode, Json<serde_json::Value>)> {
+    match result {
+        Ok(value) => Ok((StatusCode::OK, Json(value))),
+        Err(NotFoundErrorModel::ValidationError(msg)) => {
+            error!("Webhook validation failed: {}", msg);
+            Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": msg})),
+            ))
+        }
+        Err(NotFoundErrorModel::NotFoundError(msg)) => {
+            error!("Webhook {} not found", webhook_id);
+            Err((
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": msg})),
+            ))
+        }
+        Err(NotFoundErrorModel::DatabaseError(msg)) => {
+            error!("Database error on webhook {}: {}", webhook_id, msg);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": msg})),
+            ))
+        }
+    }
+}
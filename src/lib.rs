@@ -0,0 +1,57 @@
+use axum::{
+    extract::DefaultBodyLimit,
+    middleware::{from_fn, from_fn_with_state},
+    routing::get,
+    Extension, Router,
+};
+use handler::default_handler::default_handler;
+use metrics::HttpMetricsLayer;
+use middleware::{auth::authenticate, cors::cors_layer, request_id::request_id};
+use routes::{api_key_routes::api_key_routes, audit_routes::audit_routes, docs_routes::docs_routes, event_routes::event_routes, health_routes::health_routes, item_routes::item_routes, product_routes::product_routes, webhook_routes::webhook_routes};
+use sea_orm::DatabaseConnection;
+
+pub mod config;
+pub mod entities;
+pub mod handler;
+pub mod metrics;
+pub mod middleware;
+pub mod models;
+pub mod openapi;
+pub mod repositories;
+pub mod routes;
+pub mod services;
+pub mod state;
+pub mod telemetry;
+pub mod utils;
+
+pub use config::Config;
+pub use state::AppState;
+
+/// Builds the product and item API on top of the given database connection.
+pub fn build_router(config: Config, db: DatabaseConnection) -> Router {
+    app_router(AppState::new(config, db))
+}
+
+/// Builds the product and item API around an existing `AppState`, so it can be
+/// merged into another axum application.
+pub fn app_router(state: AppState) -> Router {
+    Router::new()
+        .merge(product_routes())
+        .merge(item_routes())
+        .merge(api_key_routes())
+        .merge(audit_routes())
+        .merge(webhook_routes())
+        .merge(event_routes())
+        .merge(health_routes())
+        .merge(docs_routes())
+        .route("/", get(default_handler))
+        .layer(from_fn_with_state(state.auth_service.clone(), authenticate))
+        .layer(Extension(state.limits.clone()))
+        .layer(Extension(state.idempotency_service.clone()))
+        .layer(DefaultBodyLimit::max(state.config.limits.max_body_bytes))
+        // Outside authentication, so preflights and rejections carry CORS headers
+        .layer(cors_layer(&state.config.cors))
+        .layer(HttpMetricsLayer)
+        .layer(from_fn(request_id))
+        .with_state(state)
+}
@@ -1,73 +1,124 @@
-use axum::{
-    response::{Html, IntoResponse},
-    routing::get,
-    Router,
+use std::{net::SocketAddr, sync::Arc};
+
+use practice_rust::{
+    app_router,
+    repositories::{outbox_repository::OutboxRepository, webhook_repository::WebhookRepository},
+    services::{
+        outbox_service::{LogSink, OutboxDispatcher},
+        webhook_service::WebhookService,
+    },
+    telemetry,
+    utils::{
+        db::{establish_connection, run_migrations},
+        shutdown::shutdown_signal,
+    },
+    AppState, Config,
 };
-use repositories::{item_repository::ItemRepository, product_repository::ProductRepository};
-use routes::{item_routes::item_routes, product_routes::product_routes};
-use sea_orm::DatabaseConnection;
-use services::{item_service::ItemService, product_service::ProductService};
-use utils::db::establish_connection;
-
-
-mod entities;
-mod handler;
-mod models;
-mod repositories;
-mod routes;
-mod services;
-mod utils;
+use tokio::{sync::oneshot, time::timeout};
+use tracing::{error, info, warn};
 
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::fmt::init();
+    let mut config = match Config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    if std::env::args().skip(1).any(|arg| arg == "--migrate") {
+        config.auto_migrate = true;
+    }
+
+    let telemetry = match telemetry::init(&config) {
+        Ok(telemetry) => telemetry,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
 
     // Establish database connection
-    let db = establish_connection().await;
-
-    server(db).await;
+    let db = match establish_connection(&config.database).await {
+        Ok(db) => db,
+        Err(err) => {
+            error!("Failed to connect to db: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    if config.auto_migrate {
+        if let Err(err) = run_migrations(&db, &config.database.schema).await {
+            error!("Failed to run migrations: {}", err);
+            std::process::exit(1);
+        }
+    }
+
+    let listener = match tokio::net::TcpListener::bind(config.bind_address()).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            error!("Failed to listen on {}: {}", config.bind_address(), err);
+            std::process::exit(1);
+        }
+    };
+    let shutdown_timeout = config.shutdown_timeout;
+
+    // Events are delivered at least once, so losing the tasks mid-batch is harmless
+    let webhook_service = match WebhookService::new(
+        Arc::new(WebhookRepository::new(db.clone())),
+        &config.webhooks,
+    ) {
+        Ok(webhook_service) => webhook_service,
+        Err(err) => {
+            error!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    let dispatcher = OutboxDispatcher::new(
+        Arc::new(OutboxRepository::new(db.clone())),
+        vec![Arc::new(LogSink), Arc::new(webhook_service.clone())],
+        &config.outbox,
+    )
+    .spawn();
+    let webhook_deliverer = webhook_service.spawn(&config.outbox);
+    let state = AppState::new(config, db.clone());
+    let event_stream = state.event_stream_service.clone();
+    let idempotency_purge = state.idempotency_service.clone().spawn();
+    let router = app_router(state);
+
+    // Stops accepting connections once triggered and lets in-flight requests finish
+    let (drain_tx, drain_rx) = oneshot::channel::<()>();
+    let mut server = tokio::spawn(async move {
+        // Client addresses key the rate limits of anonymous callers
+        axum::serve(listener, router.into_make_service_with_connect_info::<SocketAddr>())
+            .with_graceful_shutdown(async {
+                let _ = drain_rx.await;
+            })
+            .await
+    });
+
+    tokio::select! {
+        result = &mut server => {
+            error!("Server stopped unexpectedly: {:?}", result);
+        }
+        _ = shutdown_signal() => {
+            info!("Shutdown signal received, draining in-flight requests");
+            let _ = drain_tx.send(());
+            // Event streams never finish by themselves
+            event_stream.close();
+
+            if timeout(shutdown_timeout, &mut server).await.is_err() {
+                warn!("In-flight requests did not finish within {:?}, aborting them", shutdown_timeout);
+                server.abort();
+            }
+        }
+    }
+
+    dispatcher.abort();
+    webhook_deliverer.abort();
+    idempotency_purge.abort();
+    db.close().await.ok();
+    info!("Database pool closed, shutting down");
+    telemetry.shutdown();
 }
-
-async fn server(db: DatabaseConnection) {
-    let product_repository = ProductRepository::new(db.clone());
-    let item_repository = ItemRepository::new(db);
-
-    let product_service = ProductService::new(product_repository);
-
-    let item_service = ItemService::new(item_repository);
-
-    let default_route = get(default_handler);
-
-    let router = Router::new()
-        .merge(product_routes(product_service))
-        .merge(item_routes(item_service))
-        .route("/", default_route); 
-
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await.unwrap();
-    axum::serve(listener, router).await.unwrap();
-}
-
-
-
-// Default route handler function
-async fn default_handler() -> impl axum::response::IntoResponse {
-    let response = r#"
-📋 Welcome to the Product-Item Microservice<br><br>
-Available API Endpoints:<br>
-----------------------------------<br>
-<strong>Product Endpoints</strong>:<br> 
-🔹 GET /product       - Lists all products.<br>
-🔹 POST /product      - Create a new product.<br>
-🔹 PUT /product/{id}  - Update a product by ID.<br>
-🔹 DELETE /product/{id} - Delete a product by ID.<br><br>
-
-<strong>Item Endpoints</strong>:<br>
-🔹 GET /item/{id}         - Get an item by ID.<br>
-🔹 POST /item             - Create a new item.<br>
-🔹 PUT /item/{id}         - Update an item by ID.<br>
-🔹 DELETE /items/{id}     - Delete an item by ID.<br><br>
-
-Happy coding!<br>
-"#;
-    Html(response) 
-}
\ No newline at end of file
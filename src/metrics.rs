@@ -0,0 +1,171 @@
+use std::{
+    future::Future,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use axum::{body::Body, extract::MatchedPath, http::Request, response::Response};
+use futures::future::BoxFuture;
+use lazy_static::lazy_static;
+use prometheus::{
+    core::Collector, Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec,
+    Opts, Registry, TextEncoder,
+};
+use tower::{Layer, Service};
+use tracing::{info_span, Instrument};
+
+lazy_static! {
+    /// Registry holding every metric exposed on `GET /metrics`.
+    pub static ref REGISTRY: Registry = Registry::new();
+
+    static ref HTTP_REQUESTS_TOTAL: IntCounterVec = register(
+        IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests handled, by route and status"),
+            &["method", "route", "status"],
+        )
+        .unwrap(),
+    );
+
+    static ref HTTP_REQUEST_DURATION: HistogramVec = register(
+        HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Time spent handling HTTP requests, by route and status",
+            ),
+            &["method", "route", "status"],
+        )
+        .unwrap(),
+    );
+
+    static ref DB_QUERY_DURATION: HistogramVec = register(
+        HistogramVec::new(
+            HistogramOpts::new(
+                "db_query_duration_seconds",
+                "Time spent in repository calls, by repository method",
+            ),
+            &["repository", "method", "outcome"],
+        )
+        .unwrap(),
+    );
+
+    pub static ref DB_POOL_CONNECTIONS: IntGaugeVec = register(
+        IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Database pool connections, by state"),
+            &["state"],
+        )
+        .unwrap(),
+    );
+
+    pub static ref DB_POOL_MAX_CONNECTIONS: IntGauge = register(
+        IntGauge::new("db_pool_max_connections", "Configured size limit of the database pool")
+            .unwrap(),
+    );
+
+    pub static ref ITEMS_TOTAL: IntGauge = register(
+        IntGauge::new("catalog_items_total", "Number of items in the catalog").unwrap(),
+    );
+
+    pub static ref ITEMS_OUT_OF_STOCK: IntGauge = register(
+        IntGauge::new("catalog_items_out_of_stock", "Number of items without stock").unwrap(),
+    );
+}
+
+fn register<C: Collector + Clone + 'static>(collector: C) -> C {
+    REGISTRY
+        .register(Box::new(collector.clone()))
+        .expect("Metric registered twice");
+    collector
+}
+
+/// Renders every registered metric in the Prometheus text format.
+pub fn encode() -> String {
+    // Metrics are registered lazily; touch them so they show up before first use
+    lazy_static::initialize(&HTTP_REQUESTS_TOTAL);
+    lazy_static::initialize(&HTTP_REQUEST_DURATION);
+    lazy_static::initialize(&DB_QUERY_DURATION);
+
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&REGISTRY.gather(), &mut buffer)
+        .expect("Failed to encode metrics");
+    String::from_utf8(buffer).expect("Metrics are not valid UTF-8")
+}
+
+/// Runs a repository call in its own span and records how long it took.
+pub async fn time_query<T, E, F>(repository: &str, method: &str, query: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let span = info_span!(
+        "db.query",
+        otel.kind = "client",
+        db.repository = repository,
+        db.operation = method,
+    );
+
+    let start = Instant::now();
+    let result = query.instrument(span).await;
+    let outcome = if result.is_ok() { "ok" } else { "error" };
+
+    DB_QUERY_DURATION
+        .with_label_values(&[repository, method, outcome])
+        .observe(start.elapsed().as_secs_f64());
+
+    result
+}
+
+/// Tower layer counting requests and timing them by matched route and status.
+#[derive(Clone, Copy, Default)]
+pub struct HttpMetricsLayer;
+
+impl<S> Layer<S> for HttpMetricsLayer {
+    type Service = HttpMetrics<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HttpMetrics { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct HttpMetrics<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for HttpMetrics<S>
+where
+    S: Service<Request<Body>, Response = Response> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let method = request.method().to_string();
+        // Label by route template rather than raw path to keep cardinality bounded
+        let route = request
+            .extensions()
+            .get::<MatchedPath>()
+            .map(|path| path.as_str().to_owned())
+            .unwrap_or_else(|| "unmatched".to_owned());
+        let start = Instant::now();
+        let response = self.inner.call(request);
+
+        Box::pin(async move {
+            let response = response.await?;
+            let status = response.status().as_u16().to_string();
+            let labels = [method.as_str(), route.as_str(), status.as_str()];
+
+            HTTP_REQUESTS_TOTAL.with_label_values(&labels).inc();
+            HTTP_REQUEST_DURATION
+                .with_label_values(&labels)
+                .observe(start.elapsed().as_secs_f64());
+
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,176 @@
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts, HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use tracing::{warn, Span};
+
+use crate::{
+    middleware::{
+        rate_limit::Limits,
+        request_id::RequestId,
+    },
+    models::{
+        audit_model::AuditContextModel,
+        auth_model::{CallerModel, Permission},
+        AuthErrorModel,
+    },
+    services::auth_service::AuthService,
+};
+
+pub static X_API_KEY: HeaderName = HeaderName::from_static("x-api-key");
+
+/// Authenticated caller of a request. Taking it as a handler argument makes
+/// the route answer `401` to anonymous requests.
+#[derive(Clone, Debug)]
+pub struct Caller(pub CallerModel);
+
+impl std::ops::Deref for Caller {
+    type Target = CallerModel;
+
+    fn deref(&self) -> &CallerModel {
+        &self.0
+    }
+}
+
+/// Authenticates the `X-Api-Key` or bearer token sent with the request, if
+/// any, and makes the caller available to the handlers. Invalid credentials
+/// are rejected here; whether anonymous requests are allowed is up to the route.
+///
+/// Failed attempts count against a per-address quota, so guessing keys or
+/// tokens gets `429` before the route's own rate limit is ever reached.
+pub async fn authenticate(
+    State(service): State<AuthService>,
+    Extension(limits): Extension<Limits>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let headers = request.headers();
+    let api_key = headers
+        .get(&X_API_KEY)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned);
+    let token = headers.get(header::AUTHORIZATION).map(bearer_token);
+    if api_key.is_none() && token.is_none() {
+        return next.run(request).await;
+    }
+
+    let client = limits.client_address(&request);
+    if let Some(response) = limits.reject_auth_attempt(&client) {
+        return response;
+    }
+
+    let caller = match (api_key, token) {
+        (Some(api_key), _) => service.authenticate_api_key(api_key.trim()).await,
+        (None, Some(token)) => token.and_then(|token| service.authenticate(&token)),
+        (None, None) => return next.run(request).await,
+    };
+
+    match caller {
+        Ok(caller) => {
+            Span::current().record("subject", caller.subject.as_str());
+            request.extensions_mut().insert(Caller(caller));
+        }
+        Err(err) => {
+            limits.record_auth_failure(&client);
+            return rejection(err);
+        }
+    }
+
+    next.run(request).await
+}
+
+/// Token of an `Authorization: Bearer <token>` header; other schemes are
+/// rejected rather than mistaken for a token.
+fn bearer_token(value: &HeaderValue) -> Result<String, AuthErrorModel> {
+    let invalid = || AuthErrorModel::Unauthorized("Expected a Bearer token".to_string());
+
+    let value = value.to_str().map_err(|_| invalid())?;
+    match value.split_once(' ') {
+        Some((scheme, token))
+            if scheme.eq_ignore_ascii_case("bearer") && !token.trim().is_empty() =>
+        {
+            Ok(token.trim().to_owned())
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Route layer answering `401` to anonymous callers and `403` to callers
+/// without `permission`, e.g.
+/// `from_fn_with_state(Permission::CatalogWrite, require_permission)`.
+pub async fn require_permission(
+    State(permission): State<Permission>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match request.extensions().get::<Caller>() {
+        None => rejection(AuthErrorModel::Unauthorized(
+            "Missing bearer token or API key".to_string(),
+        )),
+        Some(caller) if !caller.has(permission) => rejection(AuthErrorModel::Forbidden(format!(
+            "Missing permission {}",
+            permission
+        ))),
+        Some(_) => next.run(request).await,
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Caller {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<Caller>().cloned().ok_or_else(|| {
+            rejection(AuthErrorModel::Unauthorized(
+                "Missing bearer token or API key".to_string(),
+            ))
+        })
+    }
+}
+
+/// Authenticated caller and ID of a request, the context changes are audited
+/// with. Like `Caller`, it makes the route answer `401` to anonymous requests.
+#[derive(Clone, Debug)]
+pub struct Actor(pub AuditContextModel);
+
+impl std::ops::Deref for Actor {
+    type Target = AuditContextModel;
+
+    fn deref(&self) -> &AuditContextModel {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Actor {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Caller(caller) = Caller::from_request_parts(parts, state).await?;
+        let request_id = parts.extensions.get::<RequestId>().map(|id| id.0.clone());
+
+        Ok(Actor(AuditContextModel::new(caller.subject, request_id)))
+    }
+}
+
+fn rejection(err: AuthErrorModel) -> Response {
+    match err {
+        AuthErrorModel::Unauthorized(msg) => {
+            warn!("Request rejected: {}", msg);
+            (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer")],
+                Json(serde_json::json!({"error": msg})),
+            )
+                .into_response()
+        }
+        AuthErrorModel::Forbidden(msg) => {
+            warn!("Request rejected: {}", msg);
+            (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": msg}))).into_response()
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowHeaders, AllowOrigin, Any, CorsLayer};
+
+use crate::{
+    config::CorsConfig,
+    middleware::{
+        idempotency::IDEMPOTENT_REPLAYED,
+        rate_limit::{RATELIMIT_LIMIT, RATELIMIT_REMAINING, RATELIMIT_RESET},
+        request_id::X_REQUEST_ID,
+    },
+};
+
+/// Builds the CORS policy of the whole API. Values were validated when the
+/// configuration was loaded, so malformed entries are simply skipped.
+pub fn cors_layer(config: &CorsConfig) -> CorsLayer {
+    let origins = if config.origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::from(Any)
+    } else {
+        AllowOrigin::list(
+            config
+                .origins
+                .iter()
+                .filter_map(|origin| HeaderValue::from_str(origin).ok()),
+        )
+    };
+
+    let headers = if config.headers.iter().any(|name| name == "*") {
+        AllowHeaders::from(Any)
+    } else {
+        AllowHeaders::list(
+            config
+                .headers
+                .iter()
+                .filter_map(|name| HeaderName::from_bytes(name.as_bytes()).ok()),
+        )
+    };
+
+    CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(
+            config
+                .methods
+                .iter()
+                .filter_map(|method| Method::from_bytes(method.as_bytes()).ok())
+                .collect::<Vec<_>>(),
+        )
+        .allow_headers(headers)
+        .allow_credentials(config.allow_credentials)
+        .expose_headers([
+            X_REQUEST_ID.clone(),
+            header::RETRY_AFTER,
+            IDEMPOTENT_REPLAYED.clone(),
+            RATELIMIT_LIMIT.clone(),
+            RATELIMIT_REMAINING.clone(),
+            RATELIMIT_RESET.clone(),
+        ])
+        .max_age(config.max_age)
+}
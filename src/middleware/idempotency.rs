@@ -0,0 +1,176 @@
+use axum::{
+    body::{self, Body, Bytes},
+    extract::{FromRequest, Request},
+    http::{header, HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use chrono::NaiveDateTime;
+use sha2::{Digest, Sha256};
+use tracing::{error, info};
+
+use crate::{
+    middleware::auth::Caller,
+    models::ErrorModel,
+    services::idempotency_service::{IdempotencyOutcome, IdempotencyService},
+};
+
+pub static IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+pub static IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+/// Route layer making retries of a request sent with an `Idempotency-Key`
+/// header safe: the first response is stored and sent again to later requests
+/// with the same key and body, while a different body gets `422`. Requests
+/// without the header are passed through. Must run inside `require_permission`
+/// so keys belong to an authenticated caller.
+pub async fn idempotency(
+    Extension(service): Extension<IdempotencyService>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let key = match request.headers().get(&IDEMPOTENCY_KEY) {
+        Some(key) => match key.to_str() {
+            Ok(key) => key.to_string(),
+            Err(_) => return error_response(StatusCode::BAD_REQUEST, "Invalid Idempotency-Key"),
+        },
+        None => return next.run(request).await,
+    };
+    let subject = match request.extensions().get::<Caller>() {
+        Some(caller) => caller.subject.clone(),
+        None => return next.run(request).await,
+    };
+
+    // Buffered through the extractor so the configured body limit still applies
+    let (parts, body) = request.into_parts();
+    let bytes = match Bytes::from_request(Request::from_parts(parts.clone(), body), &()).await {
+        Ok(bytes) => bytes,
+        Err(rejection) => return rejection.into_response(),
+    };
+    let fingerprint = fingerprint(parts.method.as_str(), parts.uri.path(), &bytes);
+    let request = Request::from_parts(parts, Body::from(bytes));
+
+    let locked_until = match service.begin(&subject, &key, &fingerprint).await {
+        Ok(IdempotencyOutcome::Proceed { locked_until }) => locked_until,
+        Ok(IdempotencyOutcome::Replay { status_code, body }) => {
+            info!("Replaying response to Idempotency-Key {}", key);
+            let status = StatusCode::from_u16(status_code).unwrap_or(StatusCode::OK);
+            return (
+                status,
+                [
+                    (
+                        header::CONTENT_TYPE,
+                        HeaderValue::from_static("application/json"),
+                    ),
+                    (
+                        IDEMPOTENT_REPLAYED.clone(),
+                        HeaderValue::from_static("true"),
+                    ),
+                ],
+                body,
+            )
+                .into_response();
+        }
+        Ok(IdempotencyOutcome::Mismatch) => {
+            return error_response(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Idempotency-Key was already used with a different request",
+            )
+        }
+        Ok(IdempotencyOutcome::InProgress) => {
+            return error_response(
+                StatusCode::CONFLICT,
+                "A request with this Idempotency-Key is still in progress",
+            )
+        }
+        Err(ErrorModel::ValidationError(msg)) => {
+            return error_response(StatusCode::BAD_REQUEST, &msg)
+        }
+        Err(ErrorModel::DatabaseError(msg)) => {
+            error!("Failed to look up Idempotency-Key: {}", msg);
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, &msg);
+        }
+    };
+
+    let claim = Claim(Some((
+        service.clone(),
+        subject.clone(),
+        key.clone(),
+        locked_until,
+    )));
+    let response = next.run(request).await;
+    let status = response.status();
+    // From here on the key is completed or released below
+    claim.disarm();
+
+    // Server errors are not final, so the key is released for a retry
+    if status.is_server_error() {
+        if let Err(ErrorModel::DatabaseError(msg) | ErrorModel::ValidationError(msg)) =
+            service.abandon(&subject, &key, locked_until).await
+        {
+            error!("Failed to release Idempotency-Key: {}", msg);
+        }
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            error!("Failed to buffer response: {}", err);
+            let _ = service.abandon(&subject, &key, locked_until).await;
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "Failed to read response");
+        }
+    };
+
+    if let Err(ErrorModel::DatabaseError(msg) | ErrorModel::ValidationError(msg)) = service
+        .complete(
+            &subject,
+            &key,
+            status.as_u16(),
+            String::from_utf8_lossy(&bytes).into_owned(),
+        )
+        .await
+    {
+        error!("Failed to store response to Idempotency-Key: {}", msg);
+    }
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// Releases a claimed key when the request is dropped before it answered,
+/// e.g. because the client timed out or the handler panicked, so a retry
+/// does not have to wait for the claim to run out.
+struct Claim(Option<(IdempotencyService, String, String, NaiveDateTime)>);
+
+impl Claim {
+    fn disarm(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        if let Some((service, subject, key, locked_until)) = self.0.take() {
+            tokio::spawn(async move {
+                if let Err(err) = service.abandon(&subject, &key, locked_until).await {
+                    error!("Failed to release Idempotency-Key: {:?}", err);
+                }
+            });
+        }
+    }
+}
+
+fn fingerprint(method: &str, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_bytes());
+    hasher.update(b" ");
+    hasher.update(path.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(serde_json::json!({"error": msg}))).into_response()
+}
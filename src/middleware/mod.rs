@@ -0,0 +1,5 @@
+pub mod auth;
+pub mod cors;
+pub mod idempotency;
+pub mod rate_limit;
+pub mod request_id;
\ No newline at end of file
@@ -0,0 +1,360 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use tokio::sync::Semaphore;
+use tracing::warn;
+
+use crate::{config::LimitsConfig, middleware::auth::Caller};
+
+pub static RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+pub static RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+pub static RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+
+/// Buckets kept per limiter. Once reached, full buckets are forgotten, and
+/// the least recently used one if none is full.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+static X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+
+/// Routes sharing a quota.
+#[derive(Clone, Copy, Debug)]
+pub enum RouteGroup {
+    /// Catalog reads.
+    Read,
+    /// Every create, update and delete.
+    Write,
+}
+
+/// Request limits shared by every route, built once from the configuration.
+#[derive(Clone)]
+pub struct Limits {
+    read: Arc<RateLimiter>,
+    write: Arc<RateLimiter>,
+    auth_failures: Arc<RateLimiter>,
+    bulk: Arc<Semaphore>,
+    trusted_proxy_hops: usize,
+}
+
+impl Limits {
+    pub fn new(config: &LimitsConfig) -> Self {
+        Self {
+            read: Arc::new(RateLimiter::new(config.read_per_minute)),
+            write: Arc::new(RateLimiter::new(config.write_per_minute)),
+            auth_failures: Arc::new(RateLimiter::new(config.auth_failures_per_minute)),
+            bulk: Arc::new(Semaphore::new(config.bulk_concurrency)),
+            trusted_proxy_hops: config.trusted_proxy_hops,
+        }
+    }
+
+    /// Address of the client that sent the request, or `unknown`. Behind
+    /// trusted proxies it is the `X-Forwarded-For` entry the outermost one
+    /// appended, as entries further left can be made up by the client.
+    pub fn client_address(&self, request: &Request) -> String {
+        let connection = request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| address.ip());
+        if self.trusted_proxy_hops == 0 {
+            return connection.map_or_else(|| "unknown".to_string(), |ip| ip.to_string());
+        }
+
+        let forwarded: Vec<&str> = request
+            .headers()
+            .get_all(&X_FORWARDED_FOR)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .collect();
+        let forwarded = forwarded
+            .len()
+            .checked_sub(self.trusted_proxy_hops)
+            .and_then(|index| forwarded[index].parse::<IpAddr>().ok());
+
+        match forwarded.or(connection) {
+            Some(ip) => ip.to_string(),
+            None => "unknown".to_string(),
+        }
+    }
+
+    /// Response to a `client` whose failed authentications used up their
+    /// quota; its credentials are not checked until the quota refills.
+    pub fn reject_auth_attempt(&self, client: &str) -> Option<Response> {
+        if self.auth_failures.limit == 0 {
+            return None;
+        }
+
+        match self.auth_failures.peek(client, Instant::now()) {
+            Decision::Allowed { .. } => None,
+            Decision::Limited { retry_after_secs } => {
+                warn!("Too many failed authentications from {}", client);
+                Some(too_many_requests(
+                    self.auth_failures.limit,
+                    retry_after_secs,
+                ))
+            }
+        }
+    }
+
+    /// Counts a failed authentication against the quota of `client`.
+    pub fn record_auth_failure(&self, client: &str) {
+        if self.auth_failures.limit > 0 {
+            self.auth_failures.check(client, Instant::now());
+        }
+    }
+
+    fn limiter(&self, group: RouteGroup) -> &RateLimiter {
+        match group {
+            RouteGroup::Read => &self.read,
+            RouteGroup::Write => &self.write,
+        }
+    }
+}
+
+/// Token bucket per client: `limit` requests, refilled continuously over a
+/// minute. A limit of 0 disables it.
+pub struct RateLimiter {
+    limit: u32,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Decision {
+    Allowed { remaining: u32, reset_secs: u64 },
+    Limited { retry_after_secs: u64 },
+}
+
+impl RateLimiter {
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token from the bucket of `client`, if it has one left.
+    pub fn check(&self, client: &str, now: Instant) -> Decision {
+        self.take(client, now, true)
+    }
+
+    /// Like `check`, without taking a token.
+    pub fn peek(&self, client: &str, now: Instant) -> Decision {
+        self.take(client, now, false)
+    }
+
+    fn take(&self, client: &str, now: Instant, consume: bool) -> Decision {
+        let limit = f64::from(self.limit);
+        let per_second = limit / 60.0;
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(client) {
+            buckets.retain(|_, bucket| {
+                bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * per_second
+                    < limit
+            });
+            if buckets.len() >= MAX_TRACKED_CLIENTS {
+                let oldest = buckets
+                    .iter()
+                    .min_by_key(|(_, bucket)| bucket.updated)
+                    .map(|(client, _)| client.clone());
+                if let Some(oldest) = oldest {
+                    buckets.remove(&oldest);
+                }
+            }
+        }
+
+        let bucket = buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: limit,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_second).min(limit);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            if consume {
+                bucket.tokens -= 1.0;
+            }
+            Decision::Allowed {
+                remaining: bucket.tokens.floor() as u32,
+                reset_secs: ((limit - bucket.tokens) / per_second).ceil() as u64,
+            }
+        } else {
+            Decision::Limited {
+                retry_after_secs: ((1.0 - bucket.tokens) / per_second).ceil().max(1.0) as u64,
+            }
+        }
+    }
+}
+
+/// Route layer applying the quota of `group`, keyed by the authenticated
+/// caller or else the client address, e.g.
+/// `from_fn_with_state(RouteGroup::Read, rate_limit)`.
+pub async fn rate_limit(
+    State(group): State<RouteGroup>,
+    Extension(limits): Extension<Limits>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let limiter = limits.limiter(group);
+    if limiter.limit == 0 {
+        return next.run(request).await;
+    }
+
+    let client = match request.extensions().get::<Caller>() {
+        Some(caller) => caller.subject.clone(),
+        None => limits.client_address(&request),
+    };
+
+    match limiter.check(&client, Instant::now()) {
+        Decision::Allowed {
+            remaining,
+            reset_secs,
+        } => {
+            let mut response = next.run(request).await;
+            set_headers(response.headers_mut(), limiter.limit, remaining, reset_secs);
+            response
+        }
+        Decision::Limited { retry_after_secs } => {
+            warn!("Rate limit of {:?} routes exceeded by {}", group, client);
+            too_many_requests(limiter.limit, retry_after_secs)
+        }
+    }
+}
+
+fn too_many_requests(limit: u32, retry_after_secs: u64) -> Response {
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(serde_json::json!({"error": "Too many requests"})),
+    )
+        .into_response();
+    let headers = response.headers_mut();
+    set_headers(headers, limit, 0, retry_after_secs);
+    headers.insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+    response
+}
+
+/// Route layer answering `503` instead of queueing once the configured number
+/// of bulk requests is already running.
+pub async fn limit_concurrency(
+    Extension(limits): Extension<Limits>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match limits.bulk.clone().try_acquire_owned() {
+        Ok(_permit) => next.run(request).await,
+        Err(_) => {
+            warn!("Bulk request rejected: too many running");
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                [(header::RETRY_AFTER, "1")],
+                Json(serde_json::json!({"error": "Too many bulk requests in progress"})),
+            )
+                .into_response()
+        }
+    }
+}
+
+fn set_headers(headers: &mut HeaderMap, limit: u32, remaining: u32, reset_secs: u64) {
+    headers.insert(RATELIMIT_LIMIT.clone(), HeaderValue::from(limit));
+    headers.insert(RATELIMIT_REMAINING.clone(), HeaderValue::from(remaining));
+    headers.insert(RATELIMIT_RESET.clone(), HeaderValue::from(reset_secs));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn bucket_empties_then_refills() {
+        let limiter = RateLimiter::new(2);
+        let start = Instant::now();
+
+        assert_eq!(
+            limiter.check("alice", start),
+            Decision::Allowed {
+                remaining: 1,
+                reset_secs: 30
+            }
+        );
+        assert!(matches!(limiter.check("alice", start), Decision::Allowed { remaining: 0, .. }));
+        assert_eq!(
+            limiter.check("alice", start),
+            Decision::Limited {
+                retry_after_secs: 30
+            }
+        );
+        // Other clients have their own bucket
+        assert!(matches!(limiter.check("bob", start), Decision::Allowed { .. }));
+
+        let later = start + Duration::from_secs(30);
+        assert!(matches!(limiter.check("alice", later), Decision::Allowed { .. }));
+    }
+
+    #[test]
+    fn peek_takes_no_token() {
+        let limiter = RateLimiter::new(1);
+        let start = Instant::now();
+
+        assert!(matches!(limiter.peek("alice", start), Decision::Allowed { .. }));
+        assert!(matches!(limiter.check("alice", start), Decision::Allowed { .. }));
+        assert!(matches!(limiter.peek("alice", start), Decision::Limited { .. }));
+    }
+
+    #[test]
+    fn tracked_clients_are_capped() {
+        let limiter = RateLimiter::new(2);
+        let start = Instant::now();
+
+        for n in 0..MAX_TRACKED_CLIENTS {
+            limiter.check(&format!("client-{}", n), start + Duration::from_millis(n as u64));
+        }
+        limiter.check("newcomer", start + Duration::from_secs(11));
+
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.len(), MAX_TRACKED_CLIENTS);
+        assert!(buckets.contains_key("newcomer"));
+        assert!(!buckets.contains_key("client-0"));
+    }
+
+    #[test]
+    fn client_address_skips_entries_added_before_trusted_proxies() {
+        let address = |hops: usize, forwarded: &str| {
+            let mut config = crate::config::Config::default().limits;
+            config.trusted_proxy_hops = hops;
+            let mut request = Request::builder()
+                .header("x-forwarded-for", forwarded)
+                .body(axum::body::Body::empty())
+                .unwrap();
+            request
+                .extensions_mut()
+                .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000))));
+            Limits::new(&config).client_address(&request)
+        };
+
+        assert_eq!(address(0, "203.0.113.9, 198.51.100.1"), "10.0.0.1");
+        assert_eq!(address(1, "203.0.113.9, 198.51.100.1"), "198.51.100.1");
+        assert_eq!(address(2, "203.0.113.9, 198.51.100.1"), "203.0.113.9");
+        // Fewer entries than proxies, or garbage, means the header is not trusted
+        assert_eq!(address(3, "203.0.113.9, 198.51.100.1"), "10.0.0.1");
+        assert_eq!(address(1, "203.0.113.9, not-an-ip"), "10.0.0.1");
+    }
+}
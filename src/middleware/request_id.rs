@@ -0,0 +1,91 @@
+use axum::{
+    body::{to_bytes, Body, HttpBody},
+    extract::Request,
+    http::{header, HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use serde_json::Value;
+use tracing::{field, info_span, Instrument};
+use uuid::Uuid;
+
+use crate::telemetry::set_parent_from_headers;
+
+pub static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest caller-supplied request ID that is accepted as is.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Largest error body the request ID gets added to; larger ones, and those
+/// of unknown length, are passed on untouched.
+const MAX_ERROR_BODY_LEN: usize = 64 * 1024;
+
+/// ID of the current request, available to handlers as an extension.
+#[derive(Clone, Debug)]
+pub struct RequestId(pub String);
+
+/// Accepts the caller's `X-Request-Id` or generates one, runs the request in a
+/// span carrying it, echoes it in the response header and adds it to JSON
+/// error bodies.
+pub async fn request_id(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&X_REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty() && value.len() <= MAX_REQUEST_ID_LEN)
+        .map(str::to_owned)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    request.extensions_mut().insert(RequestId(id.clone()));
+    let span = info_span!(
+        "request",
+        request_id = %id,
+        method = %request.method(),
+        path = %request.uri().path(),
+        subject = field::Empty,
+    );
+    set_parent_from_headers(&span, request.headers());
+
+    let mut response = next.run(request).instrument(span).await;
+    if response.status().is_client_error() || response.status().is_server_error() {
+        response = with_request_id_in_body(response, &id).await;
+    }
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(X_REQUEST_ID.clone(), value);
+    }
+
+    response
+}
+
+async fn with_request_id_in_body(response: Response, id: &str) -> Response {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+    let fits = response
+        .body()
+        .size_hint()
+        .upper()
+        .is_some_and(|len| len <= MAX_ERROR_BODY_LEN as u64);
+    if !is_json || !fits {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, MAX_ERROR_BODY_LEN).await {
+        Ok(bytes) => bytes,
+        // The body failed while being read, so there is nothing left to pass on
+        Err(_) => return Response::from_parts(parts, Body::empty()),
+    };
+
+    let body = match serde_json::from_slice::<Value>(&bytes) {
+        Ok(Value::Object(mut object)) => {
+            object.insert("request_id".to_string(), Value::String(id.to_string()));
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Body::from(Value::Object(object).to_string())
+        }
+        _ => Body::from(bytes),
+    };
+
+    Response::from_parts(parts, body)
+}
@@ -0,0 +1,37 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// API key as shown to admins; the secret itself is never stored.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyModel {
+    pub id: i32,
+    pub name: String,
+    /// Public part of the key, identifying it in logs.
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    #[serde(skip)]
+    #[schema(ignore)]
+    pub secret_hash: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateApiKeyModel {
+    pub name: String,
+    /// Permissions granted to the key, e.g. `inventory:write`.
+    pub scopes: Vec<String>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+/// A newly created or rotated key, with the plaintext secret shown only once.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct IssuedApiKeyModel {
+    #[serde(flatten)]
+    pub api_key: ApiKeyModel,
+    /// Value of the `X-Api-Key` header.
+    pub key: String,
+}
@@ -0,0 +1,94 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+
+/// Who makes a change and in which request, recorded with it in the audit log.
+#[derive(Clone, Debug)]
+pub struct AuditContextModel {
+    pub actor: String,
+    pub request_id: Option<String>,
+}
+
+impl AuditContextModel {
+    pub fn new(actor: impl Into<String>, request_id: Option<String>) -> Self {
+        Self {
+            actor: actor.into(),
+            request_id,
+        }
+    }
+}
+
+/// Kind of change an audit entry records.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl AuditAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+        }
+    }
+}
+
+/// Kind of record an audit entry is about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditEntity {
+    Product,
+    Item,
+}
+
+impl AuditEntity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditEntity::Product => "product",
+            AuditEntity::Item => "item",
+        }
+    }
+}
+
+/// One recorded change of a product or item.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuditLogModel {
+    pub id: i32,
+    /// Subject of the token or API key that made the change.
+    pub actor: String,
+    /// `create`, `update` or `delete`.
+    pub action: String,
+    /// `product` or `item`.
+    pub entity: String,
+    pub entity_id: i32,
+    /// The record before the change; missing for creates.
+    #[schema(value_type = Option<Object>)]
+    pub before: Option<Value>,
+    /// The record after the change; missing for deletes.
+    #[schema(value_type = Option<Object>)]
+    pub after: Option<Value>,
+    pub request_id: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+/// Filters of the audit log query; all are optional.
+#[derive(Clone, Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQueryModel {
+    /// `product` or `item`.
+    pub entity: Option<String>,
+    /// ID of the product or item; needs `entity`.
+    pub id: Option<i32>,
+    /// Only changes made at or after this time (UTC).
+    pub from: Option<NaiveDateTime>,
+    /// Only changes made before this time (UTC).
+    pub to: Option<NaiveDateTime>,
+    /// Only entries older than this one; pass the last ID of a page to get
+    /// the next one.
+    pub before_id: Option<i32>,
+    /// Most entries returned, newest first; 100 by default.
+    pub limit: Option<u64>,
+}
@@ -0,0 +1,88 @@
+use std::{collections::BTreeSet, fmt, str::FromStr};
+
+/// Operation a caller may be allowed to perform.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Permission {
+    /// Create and rename products.
+    CatalogWrite,
+    /// Create items and adjust their stock.
+    InventoryWrite,
+    /// Delete products and items.
+    CatalogDelete,
+    /// Create, revoke and rotate API keys.
+    ApiKeysManage,
+    /// Read the audit log.
+    AuditRead,
+    /// Manage webhook subscriptions and read their deliveries.
+    WebhooksManage,
+    /// Follow live stock and product changes.
+    InventoryRead,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 7] = [
+        Permission::CatalogWrite,
+        Permission::InventoryWrite,
+        Permission::CatalogDelete,
+        Permission::ApiKeysManage,
+        Permission::AuditRead,
+        Permission::WebhooksManage,
+        Permission::InventoryRead,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Permission::CatalogWrite => "catalog:write",
+            Permission::InventoryWrite => "inventory:write",
+            Permission::CatalogDelete => "catalog:delete",
+            Permission::ApiKeysManage => "api-keys:manage",
+            Permission::AuditRead => "audit:read",
+            Permission::WebhooksManage => "webhooks:manage",
+            Permission::InventoryRead => "inventory:read",
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Permission {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Permission::ALL
+            .into_iter()
+            .find(|permission| permission.as_str() == value)
+            .ok_or(())
+    }
+}
+
+/// Permissions granted by a role name; unknown roles grant nothing.
+pub fn role_permissions(role: &str) -> &'static [Permission] {
+    match role {
+        "admin" => &Permission::ALL,
+        "editor" => &[
+            Permission::CatalogWrite,
+            Permission::InventoryWrite,
+            Permission::InventoryRead,
+        ],
+        "warehouse" => &[Permission::InventoryWrite, Permission::InventoryRead],
+        _ => &[],
+    }
+}
+
+/// Authenticated caller of a request.
+#[derive(Clone, Debug)]
+pub struct CallerModel {
+    pub subject: String,
+    pub permissions: BTreeSet<Permission>,
+}
+
+impl CallerModel {
+    pub fn has(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
@@ -0,0 +1,210 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+
+use super::{item_model::ItemModel, product_model::WholeProductModel};
+
+/// Type of every domain event, as stored in the outbox and subscribed to by
+/// webhooks.
+pub const EVENT_TYPES: [&str; 7] = [
+    "ProductCreated",
+    "ProductUpdated",
+    "ProductDeleted",
+    "ItemCreated",
+    "ItemUpdated",
+    "ItemStockChanged",
+    "ItemDeleted",
+];
+
+/// Something that happened to a product or item, published to other services
+/// once the change is committed.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum DomainEventModel {
+    ProductCreated(WholeProductModel),
+    ProductUpdated(WholeProductModel),
+    /// Carries the product as it was before the delete.
+    ProductDeleted(WholeProductModel),
+    ItemCreated(ItemModel),
+    ItemUpdated(ItemModel),
+    /// Emitted next to `ItemUpdated` when the update changed the stock.
+    ItemStockChanged(StockChangeModel),
+    /// Carries the item as it was before the delete.
+    ItemDeleted(ItemModel),
+}
+
+/// New stock level of an item, with the previous one.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct StockChangeModel {
+    pub item_id: i32,
+    pub product_id: i32,
+    pub previous_stock: i32,
+    pub stock: i32,
+}
+
+impl DomainEventModel {
+    /// Events describing a product change; `None` stands for "did not exist".
+    pub fn for_product(
+        before: Option<&WholeProductModel>,
+        after: Option<&WholeProductModel>,
+    ) -> Vec<Self> {
+        match (before, after) {
+            (None, Some(after)) => vec![DomainEventModel::ProductCreated(after.clone())],
+            (Some(_), Some(after)) => vec![DomainEventModel::ProductUpdated(after.clone())],
+            (Some(before), None) => vec![DomainEventModel::ProductDeleted(before.clone())],
+            (None, None) => Vec::new(),
+        }
+    }
+
+    /// Events describing an item change; `None` stands for "did not exist".
+    pub fn for_item(before: Option<&ItemModel>, after: Option<&ItemModel>) -> Vec<Self> {
+        match (before, after) {
+            (None, Some(after)) => vec![DomainEventModel::ItemCreated(after.clone())],
+            (Some(before), Some(after)) => {
+                let mut events = vec![DomainEventModel::ItemUpdated(after.clone())];
+                if before.stock != after.stock {
+                    events.push(DomainEventModel::ItemStockChanged(StockChangeModel {
+                        item_id: after.id,
+                        product_id: after.product_id,
+                        previous_stock: before.stock,
+                        stock: after.stock,
+                    }));
+                }
+                events
+            }
+            (Some(before), None) => vec![DomainEventModel::ItemDeleted(before.clone())],
+            (None, None) => Vec::new(),
+        }
+    }
+
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            DomainEventModel::ProductCreated(_) => "ProductCreated",
+            DomainEventModel::ProductUpdated(_) => "ProductUpdated",
+            DomainEventModel::ProductDeleted(_) => "ProductDeleted",
+            DomainEventModel::ItemCreated(_) => "ItemCreated",
+            DomainEventModel::ItemUpdated(_) => "ItemUpdated",
+            DomainEventModel::ItemStockChanged(_) => "ItemStockChanged",
+            DomainEventModel::ItemDeleted(_) => "ItemDeleted",
+        }
+    }
+
+    /// ID of the product the event is about, or the item belongs to.
+    pub fn product_id(&self) -> i32 {
+        match self {
+            DomainEventModel::ProductCreated(product)
+            | DomainEventModel::ProductUpdated(product)
+            | DomainEventModel::ProductDeleted(product) => product.id,
+            DomainEventModel::ItemCreated(item)
+            | DomainEventModel::ItemUpdated(item)
+            | DomainEventModel::ItemDeleted(item) => item.product_id,
+            DomainEventModel::ItemStockChanged(change) => change.product_id,
+        }
+    }
+
+    /// The event's own fields, without the type tag.
+    pub fn payload(&self) -> Value {
+        match serde_json::to_value(self) {
+            Ok(Value::Object(mut event)) => event.remove("data").unwrap_or(Value::Null),
+            _ => Value::Null,
+        }
+    }
+}
+
+/// A domain event stored in the outbox, as handed to the sinks.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct OutboxEventModel {
+    /// Increases with every event; sinks can use it to drop duplicates.
+    pub id: i32,
+    pub event_type: String,
+    pub product_id: i32,
+    #[schema(value_type = Object)]
+    pub payload: Value,
+    pub created_at: NaiveDateTime,
+    /// Failed deliveries so far.
+    #[serde(skip)]
+    pub attempts: i32,
+}
+
+/// A committed inventory change, as pushed to `GET /events/stream` clients.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", content = "data")]
+pub enum InventoryChangeModel {
+    ProductCreated(ProductChangeModel),
+    ProductUpdated(ProductChangeModel),
+    ProductDeleted(ProductChangeModel),
+    /// Sent for new items too, with their initial stock.
+    ItemStockChanged(StockLevelModel),
+    /// Carries the stock the item had before the delete.
+    ItemDeleted(StockLevelModel),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProductChangeModel {
+    pub product_id: i32,
+    /// Missing for deletes.
+    pub name: Option<String>,
+}
+
+/// Current stock level of an item.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct StockLevelModel {
+    pub item_id: i32,
+    pub product_id: i32,
+    pub stock: i32,
+}
+
+impl InventoryChangeModel {
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            InventoryChangeModel::ProductCreated(_) => "ProductCreated",
+            InventoryChangeModel::ProductUpdated(_) => "ProductUpdated",
+            InventoryChangeModel::ProductDeleted(_) => "ProductDeleted",
+            InventoryChangeModel::ItemStockChanged(_) => "ItemStockChanged",
+            InventoryChangeModel::ItemDeleted(_) => "ItemDeleted",
+        }
+    }
+
+    /// ID of the product the change is about, or the item belongs to.
+    pub fn product_id(&self) -> i32 {
+        match self {
+            InventoryChangeModel::ProductCreated(product)
+            | InventoryChangeModel::ProductUpdated(product)
+            | InventoryChangeModel::ProductDeleted(product) => product.product_id,
+            InventoryChangeModel::ItemStockChanged(item)
+            | InventoryChangeModel::ItemDeleted(item) => item.product_id,
+        }
+    }
+
+    /// The change's own fields, sent as the `data` of the SSE event.
+    pub fn payload(&self) -> Value {
+        match self {
+            InventoryChangeModel::ProductCreated(product)
+            | InventoryChangeModel::ProductUpdated(product)
+            | InventoryChangeModel::ProductDeleted(product) => {
+                serde_json::to_value(product).unwrap_or(Value::Null)
+            }
+            InventoryChangeModel::ItemStockChanged(item)
+            | InventoryChangeModel::ItemDeleted(item) => {
+                serde_json::to_value(item).unwrap_or(Value::Null)
+            }
+        }
+    }
+}
+
+/// An inventory change numbered in publishing order; the number is the SSE
+/// event ID clients resume from.
+#[derive(Clone, Debug)]
+pub struct LiveEventModel {
+    pub id: u64,
+    pub change: InventoryChangeModel,
+}
+
+/// Filters of the event stream.
+#[derive(Clone, Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EventStreamQueryModel {
+    /// Only changes to this product and its items.
+    pub product_id: Option<i32>,
+}
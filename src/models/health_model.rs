@@ -0,0 +1,35 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct HealthModel {
+    pub status: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReadinessModel {
+    pub status: String,
+    pub checks: BTreeMap<String, CheckModel>,
+}
+
+impl ReadinessModel {
+    pub fn is_ready(&self) -> bool {
+        self.checks.values().all(|check| check.status == "ok")
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct CheckModel {
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct VersionModel {
+    pub version: String,
+    pub git_sha: String,
+    pub build_time: String,
+}
@@ -0,0 +1,12 @@
+use chrono::NaiveDateTime;
+
+/// A request made under an `Idempotency-Key`, with its response once known.
+#[derive(Clone, Debug)]
+pub struct IdempotencyRecordModel {
+    pub fingerprint: String,
+    pub status_code: Option<u16>,
+    pub response_body: Option<String>,
+    pub created_at: NaiveDateTime,
+    /// End of the running request's claim on the key; missing once answered.
+    pub locked_until: Option<NaiveDateTime>,
+}
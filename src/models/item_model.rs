@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
-use chrono::NaiveDateTime;
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, ToSchema)]
 pub struct ItemModel {
     pub id: i32,
     pub product_id: i32,
@@ -17,16 +17,17 @@ pub struct GetItemModel{
     pub size: String,
     pub stock: i32,
 }
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
 pub struct UpdateItemModel{
     pub size: Option<String>,
     pub color: Option<String>,
     pub stock: Option<i32>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct CreateItemModel {
     #[serde(skip_deserializing, default)]
+    #[schema(ignore)]
     pub id: Option<i32>,
     pub product_id: i32,
     pub color: String,
@@ -34,3 +35,15 @@ pub struct CreateItemModel {
     pub size: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct CreateProductItemModel {
+    pub color: String,
+    pub stock: i32,
+    pub size: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StockSummaryModel {
+    pub total_items: u64,
+    pub out_of_stock_items: u64,
+}
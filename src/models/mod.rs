@@ -1,15 +1,44 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+pub mod api_key_model;
+pub mod audit_model;
+pub mod auth_model;
+pub mod event_model;
+pub mod health_model;
+pub mod idempotency_model;
 pub mod item_model;
 pub mod product_model;
+pub mod webhook_model;
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum ErrorModel {
     ValidationError(String),
     DatabaseError(String),
 }
 
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum NotFoundErrorModel {
     ValidationError(String),
     DatabaseError(String),
     NotFoundError(String),
 }
 
+/// Why a caller was turned away.
+#[derive(Debug)]
+pub enum AuthErrorModel {
+    /// No or an invalid credential was presented.
+    Unauthorized(String),
+    /// The caller lacks a permission the operation needs.
+    Forbidden(String),
+}
+
+/// JSON body of every 4xx/5xx response.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct ErrorBodyModel {
+    pub error: String,
+    /// ID of the request, also sent in the `X-Request-Id` header.
+    pub request_id: Option<String>,
+}
@@ -1,9 +1,10 @@
 use serde::{Deserialize, Serialize};
 use chrono::NaiveDateTime;
+use utoipa::ToSchema;
 
-use super::item_model::ItemModel;
+use super::item_model::{CreateProductItemModel, ItemModel};
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
 pub struct WholeProductModel{
     pub id: i32,
     pub name: String,
@@ -20,19 +21,21 @@ pub struct ProductModel{
     pub updated_at: NaiveDateTime,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreateProductModal{
     pub name: String,
     pub description: Option<String>,
+    #[serde(default)]
+    pub items: Vec<CreateProductItemModel>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
 pub struct UpdateProductModal{
     pub name: Option<String>,
     pub description: Option<String>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
 pub struct ProductItemModel{
     pub id: i32,
     pub name: String,
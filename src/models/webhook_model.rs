@@ -0,0 +1,89 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// A partner endpoint receiving domain events, as shown to admins.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookModel {
+    pub id: i32,
+    pub url: String,
+    /// Domain events sent to the endpoint, e.g. `ItemStockChanged`.
+    pub event_types: Vec<String>,
+    /// Paused subscriptions get no new deliveries.
+    pub active: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    #[serde(skip)]
+    #[schema(ignore)]
+    pub secret: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateWebhookModel {
+    /// `http` or `https` URL the events are POSTed to.
+    pub url: String,
+    pub event_types: Vec<String>,
+    /// Key of the HMAC signature; generated when missing.
+    pub secret: Option<String>,
+}
+
+/// Changes to a subscription; missing fields are left as they are.
+#[derive(Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct UpdateWebhookModel {
+    pub url: Option<String>,
+    pub event_types: Option<Vec<String>>,
+    pub secret: Option<String>,
+    pub active: Option<bool>,
+}
+
+/// A newly created subscription, with its signing secret shown only once.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct IssuedWebhookModel {
+    #[serde(flatten)]
+    pub webhook: WebhookModel,
+    pub secret: String,
+}
+
+/// Where a delivery stands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WebhookDeliveryStatus {
+    /// Waiting for its first or next attempt.
+    Pending,
+    /// The endpoint answered with a 2xx status.
+    Delivered,
+    /// Every attempt failed; it is not retried anymore.
+    Dead,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WebhookDeliveryStatus::Pending => "pending",
+            WebhookDeliveryStatus::Delivered => "delivered",
+            WebhookDeliveryStatus::Dead => "dead",
+        }
+    }
+}
+
+/// One event sent, or to be sent, to a subscription.
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookDeliveryModel {
+    pub id: i32,
+    pub subscription_id: i32,
+    /// ID of the domain event, also sent in the `X-Webhook-Event-Id` header.
+    pub event_id: i32,
+    pub event_type: String,
+    /// JSON body POSTed to the endpoint.
+    #[schema(value_type = Object)]
+    pub payload: Value,
+    /// `pending`, `delivered` or `dead`.
+    pub status: String,
+    pub attempts: i32,
+    /// HTTP status of the last answer, if the endpoint answered.
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub delivered_at: Option<NaiveDateTime>,
+}
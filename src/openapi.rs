@@ -0,0 +1,74 @@
+use utoipa::{
+    openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+
+use crate::handler::{
+    api_key_handler, audit_handler, event_handler, health_handler, item_handler, metrics_handler,
+    product_handler, webhook_handler,
+};
+
+/// OpenAPI document generated from the handler annotations and the `models` structs.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Product-Item Microservice"),
+    paths(
+        product_handler::get_all_products,
+        product_handler::create_product,
+        product_handler::update_product,
+        product_handler::delete_product,
+        item_handler::get_item_by_id,
+        item_handler::create_item,
+        item_handler::update_item,
+        item_handler::delete_item,
+        api_key_handler::get_all_api_keys,
+        api_key_handler::create_api_key,
+        api_key_handler::rotate_api_key,
+        api_key_handler::revoke_api_key,
+        audit_handler::get_audit_logs,
+        webhook_handler::get_all_webhooks,
+        webhook_handler::create_webhook,
+        webhook_handler::get_webhook_by_id,
+        webhook_handler::update_webhook,
+        webhook_handler::delete_webhook,
+        webhook_handler::get_webhook_deliveries,
+        event_handler::stream_events,
+        health_handler::liveness,
+        health_handler::readiness,
+        health_handler::version,
+        metrics_handler::get_metrics,
+    ),
+    modifiers(&SecuritySchemes),
+    tags(
+        (name = "products", description = "Products and the items created with them"),
+        (name = "items", description = "Items and their stock"),
+        (name = "api-keys", description = "Credentials of machine clients"),
+        (name = "audit", description = "Who changed which product or item, and how"),
+        (name = "webhooks", description = "Signed event notifications to partner endpoints"),
+        (name = "events", description = "Live inventory changes over Server-Sent Events"),
+        (name = "operations", description = "Probes, build information and metrics"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Declares the `bearer` and `api_key` schemes the write endpoints accept.
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-Api-Key"))),
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, Set,
+};
+use tracing::instrument;
+
+use crate::{
+    entities::api_key,
+    metrics::time_query,
+    models::{api_key_model::ApiKeyModel, ErrorModel, NotFoundErrorModel},
+};
+
+use super::store::ApiKeyStore;
+
+#[derive(Clone)]
+pub struct ApiKeyRepository<C = DatabaseConnection> {
+    db: C,
+}
+
+impl<C: ConnectionTrait> ApiKeyRepository<C> {
+    pub fn new(db: C) -> Self {
+        Self { db }
+    }
+
+    #[instrument(skip(self, secret_hash))]
+    pub async fn create_api_key_in_db(
+        &self,
+        name: String,
+        prefix: String,
+        secret_hash: String,
+        scopes: Vec<String>,
+        expires_at: Option<NaiveDateTime>,
+    ) -> Result<ApiKeyModel, ErrorModel> {
+        let api_key_model = api_key::ActiveModel {
+            name: Set(name),
+            prefix: Set(prefix),
+            secret_hash: Set(secret_hash),
+            scopes: Set(scopes.join(" ")),
+            expires_at: Set(expires_at),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+
+        match api_key_model.insert(&self.db).await {
+            Ok(inserted_api_key) => Ok(to_api_key_model(inserted_api_key)),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to create API key: {}",
+                err
+            ))),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_all_api_keys_from_db(&self) -> Result<Vec<ApiKeyModel>, ErrorModel> {
+        match api_key::Entity::find()
+            .order_by_asc(api_key::Column::Id)
+            .all(&self.db)
+            .await
+        {
+            Ok(api_keys) => Ok(api_keys.into_iter().map(to_api_key_model).collect()),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to fetch API keys: {}",
+                err
+            ))),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_api_key_by_prefix_from_db(
+        &self,
+        prefix: &str,
+    ) -> Result<ApiKeyModel, NotFoundErrorModel> {
+        match api_key::Entity::find()
+            .filter(api_key::Column::Prefix.eq(prefix))
+            .one(&self.db)
+            .await
+        {
+            Ok(Some(api_key)) => Ok(to_api_key_model(api_key)),
+            Ok(None) => Err(NotFoundErrorModel::NotFoundError(
+                "API key not found".to_string(),
+            )),
+            Err(err) => Err(NotFoundErrorModel::DatabaseError(format!(
+                "Failed to fetch API key: {}",
+                err
+            ))),
+        }
+    }
+
+    #[instrument(skip(self, secret_hash))]
+    pub async fn rotate_api_key_in_db(
+        &self,
+        api_key_id: i32,
+        prefix: String,
+        secret_hash: String,
+    ) -> Result<ApiKeyModel, NotFoundErrorModel> {
+        self.update_api_key(api_key_id, |api_key| {
+            api_key.prefix = Set(prefix);
+            api_key.secret_hash = Set(secret_hash);
+        })
+        .await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn revoke_api_key_in_db(
+        &self,
+        api_key_id: i32,
+    ) -> Result<ApiKeyModel, NotFoundErrorModel> {
+        self.update_api_key(api_key_id, |api_key| {
+            api_key.revoked_at = Set(Some(Utc::now().naive_utc()));
+        })
+        .await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn touch_api_key_in_db(&self, api_key_id: i32) -> Result<(), ErrorModel> {
+        let api_key = api_key::ActiveModel {
+            id: Set(api_key_id),
+            last_used_at: Set(Some(Utc::now().naive_utc())),
+            ..Default::default()
+        };
+
+        match api_key.update(&self.db).await {
+            Ok(_) => Ok(()),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to record API key use: {}",
+                err
+            ))),
+        }
+    }
+
+    async fn update_api_key(
+        &self,
+        api_key_id: i32,
+        change: impl FnOnce(&mut api_key::ActiveModel),
+    ) -> Result<ApiKeyModel, NotFoundErrorModel> {
+        match api_key::Entity::find_by_id(api_key_id).one(&self.db).await {
+            Ok(Some(api_key)) => {
+                let mut updated_api_key: api_key::ActiveModel = api_key.into();
+                change(&mut updated_api_key);
+
+                match updated_api_key.update(&self.db).await {
+                    Ok(updated_api_key) => Ok(to_api_key_model(updated_api_key)),
+                    Err(err) => Err(NotFoundErrorModel::DatabaseError(format!(
+                        "Failed to update API key: {}",
+                        err
+                    ))),
+                }
+            }
+            Ok(None) => Err(NotFoundErrorModel::NotFoundError(format!(
+                "API key with ID {} not found",
+                api_key_id
+            ))),
+            Err(err) => Err(NotFoundErrorModel::DatabaseError(format!(
+                "Failed to retrieve API key: {}",
+                err
+            ))),
+        }
+    }
+}
+
+pub(crate) fn to_api_key_model(api_key: api_key::Model) -> ApiKeyModel {
+    ApiKeyModel {
+        id: api_key.id,
+        name: api_key.name,
+        prefix: api_key.prefix,
+        scopes: api_key.scopes.split_whitespace().map(str::to_string).collect(),
+        expires_at: api_key.expires_at,
+        last_used_at: api_key.last_used_at,
+        revoked_at: api_key.revoked_at,
+        created_at: api_key.created_at,
+        secret_hash: api_key.secret_hash,
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiKeyStore for ApiKeyRepository {
+    async fn create_api_key_in_db(
+        &self,
+        name: String,
+        prefix: String,
+        secret_hash: String,
+        scopes: Vec<String>,
+        expires_at: Option<NaiveDateTime>,
+    ) -> Result<ApiKeyModel, ErrorModel> {
+        time_query(
+            "api_key",
+            "create_api_key_in_db",
+            ApiKeyRepository::create_api_key_in_db(
+                self,
+                name,
+                prefix,
+                secret_hash,
+                scopes,
+                expires_at,
+            ),
+        )
+        .await
+    }
+
+    async fn get_all_api_keys_from_db(&self) -> Result<Vec<ApiKeyModel>, ErrorModel> {
+        time_query(
+            "api_key",
+            "get_all_api_keys_from_db",
+            ApiKeyRepository::get_all_api_keys_from_db(self),
+        )
+        .await
+    }
+
+    async fn get_api_key_by_prefix_from_db(
+        &self,
+        prefix: &str,
+    ) -> Result<ApiKeyModel, NotFoundErrorModel> {
+        time_query(
+            "api_key",
+            "get_api_key_by_prefix_from_db",
+            ApiKeyRepository::get_api_key_by_prefix_from_db(self, prefix),
+        )
+        .await
+    }
+
+    async fn rotate_api_key_in_db(
+        &self,
+        api_key_id: i32,
+        prefix: String,
+        secret_hash: String,
+    ) -> Result<ApiKeyModel, NotFoundErrorModel> {
+        time_query(
+            "api_key",
+            "rotate_api_key_in_db",
+            ApiKeyRepository::rotate_api_key_in_db(self, api_key_id, prefix, secret_hash),
+        )
+        .await
+    }
+
+    async fn revoke_api_key_in_db(
+        &self,
+        api_key_id: i32,
+    ) -> Result<ApiKeyModel, NotFoundErrorModel> {
+        time_query(
+            "api_key",
+            "revoke_api_key_in_db",
+            ApiKeyRepository::revoke_api_key_in_db(self, api_key_id),
+        )
+        .await
+    }
+
+    async fn touch_api_key_in_db(&self, api_key_id: i32) -> Result<(), ErrorModel> {
+        time_query(
+            "api_key",
+            "touch_api_key_in_db",
+            ApiKeyRepository::touch_api_key_in_db(self, api_key_id),
+        )
+        .await
+    }
+}
@@ -0,0 +1,130 @@
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set,
+};
+use serde::Serialize;
+use tracing::instrument;
+
+use crate::{
+    entities::audit_log,
+    metrics::time_query,
+    models::{
+        audit_model::{
+            AuditAction, AuditContextModel, AuditEntity, AuditLogModel, AuditQueryModel,
+        },
+        ErrorModel,
+    },
+};
+
+use super::store::AuditStore;
+
+/// Entries returned by a query that sets no `limit`.
+pub const DEFAULT_AUDIT_LIMIT: u64 = 100;
+
+#[derive(Clone)]
+pub struct AuditRepository<C = DatabaseConnection> {
+    db: C,
+}
+
+impl<C: ConnectionTrait> AuditRepository<C> {
+    pub fn new(db: C) -> Self {
+        Self { db }
+    }
+
+    /// Records a change; run it on the connection of the change itself so
+    /// both are committed or rolled back together.
+    #[instrument(skip(self, context, before, after))]
+    pub async fn insert_audit_log_in_db<T: Serialize>(
+        &self,
+        context: &AuditContextModel,
+        action: AuditAction,
+        entity: AuditEntity,
+        entity_id: i32,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<(), DbErr> {
+        let entry = audit_log::ActiveModel {
+            actor: Set(context.actor.clone()),
+            action: Set(action.as_str().to_string()),
+            entity_type: Set(entity.as_str().to_string()),
+            entity_id: Set(entity_id),
+            before: Set(before.and_then(snapshot)),
+            after: Set(after.and_then(snapshot)),
+            request_id: Set(context.request_id.clone()),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+
+        entry.insert(&self.db).await.map(|_| ())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_audit_logs_from_db(
+        &self,
+        query: AuditQueryModel,
+    ) -> Result<Vec<AuditLogModel>, ErrorModel> {
+        let mut select = audit_log::Entity::find();
+        if let Some(entity) = query.entity {
+            select = select.filter(audit_log::Column::EntityType.eq(entity));
+        }
+        if let Some(entity_id) = query.id {
+            select = select.filter(audit_log::Column::EntityId.eq(entity_id));
+        }
+        if let Some(from) = query.from {
+            select = select.filter(audit_log::Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = query.to {
+            select = select.filter(audit_log::Column::CreatedAt.lt(to));
+        }
+        if let Some(before_id) = query.before_id {
+            select = select.filter(audit_log::Column::Id.lt(before_id));
+        }
+
+        match select
+            .order_by_desc(audit_log::Column::Id)
+            .limit(query.limit.unwrap_or(DEFAULT_AUDIT_LIMIT))
+            .all(&self.db)
+            .await
+        {
+            Ok(entries) => Ok(entries.into_iter().map(to_audit_log_model).collect()),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to fetch audit log: {}",
+                err
+            ))),
+        }
+    }
+}
+
+pub(crate) fn snapshot<T: Serialize>(record: &T) -> Option<serde_json::Value> {
+    serde_json::to_value(record).ok()
+}
+
+pub(crate) fn to_audit_log_model(entry: audit_log::Model) -> AuditLogModel {
+    AuditLogModel {
+        id: entry.id,
+        actor: entry.actor,
+        action: entry.action,
+        entity: entry.entity_type,
+        entity_id: entry.entity_id,
+        before: entry.before,
+        after: entry.after,
+        request_id: entry.request_id,
+        created_at: entry.created_at,
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditStore for AuditRepository {
+    async fn get_audit_logs_from_db(
+        &self,
+        query: AuditQueryModel,
+    ) -> Result<Vec<AuditLogModel>, ErrorModel> {
+        time_query(
+            "audit",
+            "get_audit_logs_from_db",
+            AuditRepository::get_audit_logs_from_db(self, query),
+        )
+        .await
+    }
+}
@@ -0,0 +1,333 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait,
+    DatabaseConnection, EntityTrait, QueryFilter, Set, SqlErr,
+};
+use tracing::instrument;
+
+use crate::{
+    entities::idempotency_key,
+    metrics::time_query,
+    models::{idempotency_model::IdempotencyRecordModel, ErrorModel},
+};
+
+use super::store::IdempotencyStore;
+
+#[derive(Clone)]
+pub struct IdempotencyRepository<C = DatabaseConnection> {
+    db: C,
+}
+
+impl<C: ConnectionTrait> IdempotencyRepository<C> {
+    pub fn new(db: C) -> Self {
+        Self { db }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_idempotency_key_from_db(
+        &self,
+        subject: &str,
+        key: &str,
+    ) -> Result<Option<IdempotencyRecordModel>, ErrorModel> {
+        match idempotency_key::Entity::find()
+            .filter(idempotency_key::Column::Subject.eq(subject))
+            .filter(idempotency_key::Column::Key.eq(key))
+            .one(&self.db)
+            .await
+        {
+            Ok(record) => Ok(record.map(to_idempotency_record_model)),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to fetch idempotency key: {}",
+                err
+            ))),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn create_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+        fingerprint: &str,
+        locked_until: NaiveDateTime,
+    ) -> Result<bool, ErrorModel> {
+        let record = idempotency_key::ActiveModel {
+            subject: Set(subject.to_string()),
+            key: Set(key.to_string()),
+            fingerprint: Set(fingerprint.to_string()),
+            created_at: Set(Utc::now().naive_utc()),
+            locked_until: Set(Some(locked_until)),
+            ..Default::default()
+        };
+
+        match record.insert(&self.db).await {
+            Ok(_) => Ok(true),
+            // Another request claimed the key first
+            Err(err) if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                Ok(false)
+            }
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to store idempotency key: {}",
+                err
+            ))),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn take_over_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+        now: NaiveDateTime,
+        locked_until: NaiveDateTime,
+    ) -> Result<bool, ErrorModel> {
+        // A single conditional update, so only one of several retries wins
+        match idempotency_key::Entity::update_many()
+            .col_expr(
+                idempotency_key::Column::LockedUntil,
+                Expr::value(Some(locked_until)),
+            )
+            .filter(idempotency_key::Column::Subject.eq(subject))
+            .filter(idempotency_key::Column::Key.eq(key))
+            .filter(idempotency_key::Column::StatusCode.is_null())
+            .filter(
+                Condition::any()
+                    .add(idempotency_key::Column::LockedUntil.is_null())
+                    .add(idempotency_key::Column::LockedUntil.lt(now)),
+            )
+            .exec(&self.db)
+            .await
+        {
+            Ok(result) => Ok(result.rows_affected == 1),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to take over idempotency key: {}",
+                err
+            ))),
+        }
+    }
+
+    #[instrument(skip(self, response_body))]
+    pub async fn complete_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+        status_code: u16,
+        response_body: String,
+    ) -> Result<(), ErrorModel> {
+        match idempotency_key::Entity::update_many()
+            .col_expr(
+                idempotency_key::Column::StatusCode,
+                (status_code as i32).into(),
+            )
+            .col_expr(
+                idempotency_key::Column::ResponseBody,
+                Some(response_body).into(),
+            )
+            .col_expr(
+                idempotency_key::Column::LockedUntil,
+                Expr::value(Option::<NaiveDateTime>::None),
+            )
+            .filter(idempotency_key::Column::Subject.eq(subject))
+            .filter(idempotency_key::Column::Key.eq(key))
+            .exec(&self.db)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to store idempotent response: {}",
+                err
+            ))),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn delete_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+    ) -> Result<(), ErrorModel> {
+        match idempotency_key::Entity::delete_many()
+            .filter(idempotency_key::Column::Subject.eq(subject))
+            .filter(idempotency_key::Column::Key.eq(key))
+            .exec(&self.db)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to delete idempotency key: {}",
+                err
+            ))),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn release_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+        locked_until: NaiveDateTime,
+    ) -> Result<(), ErrorModel> {
+        match idempotency_key::Entity::delete_many()
+            .filter(idempotency_key::Column::Subject.eq(subject))
+            .filter(idempotency_key::Column::Key.eq(key))
+            .filter(idempotency_key::Column::StatusCode.is_null())
+            .filter(idempotency_key::Column::LockedUntil.eq(locked_until))
+            .exec(&self.db)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to release idempotency key: {}",
+                err
+            ))),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn purge_idempotency_keys_in_db(
+        &self,
+        created_before: NaiveDateTime,
+    ) -> Result<u64, ErrorModel> {
+        match idempotency_key::Entity::delete_many()
+            .filter(idempotency_key::Column::CreatedAt.lt(created_before))
+            .exec(&self.db)
+            .await
+        {
+            Ok(result) => Ok(result.rows_affected),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to purge idempotency keys: {}",
+                err
+            ))),
+        }
+    }
+}
+
+pub(crate) fn to_idempotency_record_model(
+    record: idempotency_key::Model,
+) -> IdempotencyRecordModel {
+    IdempotencyRecordModel {
+        fingerprint: record.fingerprint,
+        status_code: record.status_code.map(|status_code| status_code as u16),
+        response_body: record.response_body,
+        created_at: record.created_at,
+        locked_until: record.locked_until,
+    }
+}
+
+#[async_trait::async_trait]
+impl IdempotencyStore for IdempotencyRepository {
+    async fn get_idempotency_key_from_db(
+        &self,
+        subject: &str,
+        key: &str,
+    ) -> Result<Option<IdempotencyRecordModel>, ErrorModel> {
+        time_query(
+            "idempotency",
+            "get_idempotency_key_from_db",
+            IdempotencyRepository::get_idempotency_key_from_db(self, subject, key),
+        )
+        .await
+    }
+
+    async fn create_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+        fingerprint: &str,
+        locked_until: NaiveDateTime,
+    ) -> Result<bool, ErrorModel> {
+        time_query(
+            "idempotency",
+            "create_idempotency_key_in_db",
+            IdempotencyRepository::create_idempotency_key_in_db(
+                self,
+                subject,
+                key,
+                fingerprint,
+                locked_until,
+            ),
+        )
+        .await
+    }
+
+    async fn take_over_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+        now: NaiveDateTime,
+        locked_until: NaiveDateTime,
+    ) -> Result<bool, ErrorModel> {
+        time_query(
+            "idempotency",
+            "take_over_idempotency_key_in_db",
+            IdempotencyRepository::take_over_idempotency_key_in_db(
+                self,
+                subject,
+                key,
+                now,
+                locked_until,
+            ),
+        )
+        .await
+    }
+
+    async fn complete_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+        status_code: u16,
+        response_body: String,
+    ) -> Result<(), ErrorModel> {
+        time_query(
+            "idempotency",
+            "complete_idempotency_key_in_db",
+            IdempotencyRepository::complete_idempotency_key_in_db(
+                self,
+                subject,
+                key,
+                status_code,
+                response_body,
+            ),
+        )
+        .await
+    }
+
+    async fn delete_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+    ) -> Result<(), ErrorModel> {
+        time_query(
+            "idempotency",
+            "delete_idempotency_key_in_db",
+            IdempotencyRepository::delete_idempotency_key_in_db(self, subject, key),
+        )
+        .await
+    }
+
+    async fn release_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+        locked_until: NaiveDateTime,
+    ) -> Result<(), ErrorModel> {
+        time_query(
+            "idempotency",
+            "release_idempotency_key_in_db",
+            IdempotencyRepository::release_idempotency_key_in_db(self, subject, key, locked_until),
+        )
+        .await
+    }
+
+    async fn purge_idempotency_keys_in_db(
+        &self,
+        created_before: NaiveDateTime,
+    ) -> Result<u64, ErrorModel> {
+        time_query(
+            "idempotency",
+            "purge_idempotency_keys_in_db",
+            IdempotencyRepository::purge_idempotency_keys_in_db(self, created_before),
+        )
+        .await
+    }
+}
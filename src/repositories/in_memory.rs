@@ -0,0 +1,956 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use chrono::{NaiveDateTime, Utc};
+
+use crate::{
+    entities::{
+        api_key, audit_log, idempotency_key, item, outbox, product, webhook_delivery,
+        webhook_subscription,
+    },
+    models::{
+        api_key_model::ApiKeyModel,
+        audit_model::{
+            AuditAction, AuditContextModel, AuditEntity, AuditLogModel, AuditQueryModel,
+        },
+        event_model::{DomainEventModel, OutboxEventModel},
+        idempotency_model::IdempotencyRecordModel,
+        item_model::{CreateItemModel, ItemModel, StockSummaryModel, UpdateItemModel},
+        product_model::{
+            CreateProductModal, ProductItemModel, UpdateProductModal, WholeProductModel,
+        },
+        webhook_model::{
+            UpdateWebhookModel, WebhookDeliveryModel, WebhookDeliveryStatus, WebhookModel,
+        },
+        ErrorModel, NotFoundErrorModel,
+    },
+};
+
+use super::{
+    api_key_repository::to_api_key_model,
+    audit_repository::{snapshot, to_audit_log_model, DEFAULT_AUDIT_LIMIT},
+    idempotency_repository::to_idempotency_record_model,
+    outbox_repository::to_outbox_event_model,
+    store::{
+        ApiKeyStore, AuditStore, IdempotencyStore, ItemStore, OutboxStore, ProductStore,
+        WebhookStore,
+    },
+    webhook_repository::{to_webhook_delivery_model, to_webhook_model},
+};
+
+/// Every store trait backed by process memory instead of Postgres.
+///
+/// Clones share the same data, so one instance can back both services. The
+/// foreign key between items and products is enforced the same way the
+/// database does it.
+#[derive(Clone, Default)]
+pub struct InMemoryStore {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    last_product_id: i32,
+    last_item_id: i32,
+    last_api_key_id: i32,
+    last_idempotency_key_id: i32,
+    last_audit_log_id: i32,
+    last_outbox_id: i32,
+    last_webhook_id: i32,
+    last_webhook_delivery_id: i32,
+    products: BTreeMap<i32, product::Model>,
+    items: BTreeMap<i32, item::Model>,
+    api_keys: BTreeMap<i32, api_key::Model>,
+    idempotency_keys: BTreeMap<(String, String), idempotency_key::Model>,
+    audit_logs: Vec<audit_log::Model>,
+    outbox: BTreeMap<i32, outbox::Model>,
+    webhooks: BTreeMap<i32, webhook_subscription::Model>,
+    webhook_deliveries: BTreeMap<i32, webhook_delivery::Model>,
+}
+
+impl InMemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl State {
+    fn insert_item(
+        &mut self,
+        request: CreateItemModel,
+        context: &AuditContextModel,
+    ) -> Result<ItemModel, ErrorModel> {
+        if !self.products.contains_key(&request.product_id) {
+            return Err(ErrorModel::DatabaseError(
+                "Failed to create item".to_string(),
+            ));
+        }
+
+        self.last_item_id += 1;
+        let inserted_item = item::Model {
+            id: self.last_item_id,
+            product_id: request.product_id,
+            size: request.size,
+            color: request.color,
+            stock: request.stock,
+        };
+        self.items.insert(inserted_item.id, inserted_item.clone());
+
+        let item = to_item_model(inserted_item);
+        self.record_audit(
+            context,
+            AuditAction::Create,
+            AuditEntity::Item,
+            item.id,
+            None,
+            Some(&item),
+        );
+        self.queue_events(DomainEventModel::for_item(None, Some(&item)));
+        Ok(item)
+    }
+
+    fn queue_events(&mut self, events: Vec<DomainEventModel>) {
+        let now = Utc::now().naive_utc();
+
+        for event in events {
+            self.last_outbox_id += 1;
+            self.outbox.insert(
+                self.last_outbox_id,
+                outbox::Model {
+                    id: self.last_outbox_id,
+                    event_type: event.event_type().to_string(),
+                    product_id: event.product_id(),
+                    payload: event.payload(),
+                    created_at: now,
+                    available_at: now,
+                    attempts: 0,
+                    last_error: None,
+                    sent_at: None,
+                },
+            );
+        }
+    }
+
+    fn record_audit<T: serde::Serialize>(
+        &mut self,
+        context: &AuditContextModel,
+        action: AuditAction,
+        entity: AuditEntity,
+        entity_id: i32,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) {
+        self.last_audit_log_id += 1;
+        self.audit_logs.push(audit_log::Model {
+            id: self.last_audit_log_id,
+            actor: context.actor.clone(),
+            action: action.as_str().to_string(),
+            entity_type: entity.as_str().to_string(),
+            entity_id,
+            before: before.and_then(snapshot),
+            after: after.and_then(snapshot),
+            request_id: context.request_id.clone(),
+            created_at: Utc::now().naive_utc(),
+        });
+    }
+
+    fn items_of(&self, product_id: i32) -> Vec<ItemModel> {
+        self.items
+            .values()
+            .filter(|item| item.product_id == product_id)
+            .cloned()
+            .map(to_item_model)
+            .collect()
+    }
+}
+
+impl State {
+    fn update_api_key(
+        &mut self,
+        api_key_id: i32,
+        change: impl FnOnce(&mut api_key::Model),
+    ) -> Result<ApiKeyModel, NotFoundErrorModel> {
+        match self.api_keys.get_mut(&api_key_id) {
+            Some(api_key) => {
+                change(api_key);
+                Ok(to_api_key_model(api_key.clone()))
+            }
+            None => Err(NotFoundErrorModel::NotFoundError(format!(
+                "API key with ID {} not found",
+                api_key_id
+            ))),
+        }
+    }
+}
+
+fn to_whole_product_model(product: &product::Model) -> WholeProductModel {
+    WholeProductModel {
+        id: product.id,
+        name: product.name.clone(),
+        description: product.description.clone(),
+        created_at: product.created_at,
+        updated_at: product.updated_at,
+    }
+}
+
+fn to_item_model(item: item::Model) -> ItemModel {
+    ItemModel {
+        id: item.id,
+        product_id: item.product_id,
+        color: item.color,
+        stock: item.stock,
+        size: item.size,
+    }
+}
+
+#[async_trait::async_trait]
+impl ProductStore for InMemoryStore {
+    async fn create_product_in_db(
+        &self,
+        request: CreateProductModal,
+        context: &AuditContextModel,
+    ) -> Result<ProductItemModel, ErrorModel> {
+        let now = Utc::now().naive_utc();
+        let mut state = self.lock();
+
+        state.last_product_id += 1;
+        let product = product::Model {
+            id: state.last_product_id,
+            name: request.name,
+            description: request.description,
+            created_at: now,
+            updated_at: now,
+        };
+        state.products.insert(product.id, product.clone());
+        let created = to_whole_product_model(&product);
+        state.record_audit(
+            context,
+            AuditAction::Create,
+            AuditEntity::Product,
+            product.id,
+            None,
+            Some(&created),
+        );
+        state.queue_events(DomainEventModel::for_product(None, Some(&created)));
+
+        let mut items = Vec::with_capacity(request.items.len());
+        for item_data in request.items {
+            let item = state.insert_item(
+                CreateItemModel {
+                    id: None,
+                    product_id: product.id,
+                    color: item_data.color,
+                    stock: item_data.stock,
+                    size: item_data.size,
+                },
+                context,
+            )?;
+            items.push(item);
+        }
+
+        Ok(ProductItemModel {
+            id: product.id,
+            name: product.name,
+            description: product.description,
+            items,
+        })
+    }
+
+    async fn get_all_products_from_db(&self) -> Result<Vec<ProductItemModel>, ErrorModel> {
+        let state = self.lock();
+
+        Ok(state
+            .products
+            .values()
+            .map(|product| ProductItemModel {
+                id: product.id,
+                name: product.name.clone(),
+                description: product.description.clone(),
+                items: state.items_of(product.id),
+            })
+            .collect())
+    }
+
+    async fn update_product_in_db(
+        &self,
+        product_id: i32,
+        product_data: UpdateProductModal,
+        context: &AuditContextModel,
+    ) -> Result<WholeProductModel, NotFoundErrorModel> {
+        let mut state = self.lock();
+
+        match state.products.get_mut(&product_id) {
+            Some(product) => {
+                let before = to_whole_product_model(product);
+                if let Some(name) = product_data.name {
+                    product.name = name;
+                }
+                product.description = product_data.description;
+                product.updated_at = Utc::now().naive_utc();
+
+                let after = to_whole_product_model(product);
+                state.record_audit(
+                    context,
+                    AuditAction::Update,
+                    AuditEntity::Product,
+                    product_id,
+                    Some(&before),
+                    Some(&after),
+                );
+                state.queue_events(DomainEventModel::for_product(Some(&before), Some(&after)));
+                Ok(after)
+            }
+            None => Err(NotFoundErrorModel::NotFoundError(
+                "Product not found".to_string(),
+            )),
+        }
+    }
+
+    async fn delete_product_in_db(
+        &self,
+        product_id: i32,
+        context: &AuditContextModel,
+    ) -> Result<bool, NotFoundErrorModel> {
+        let mut state = self.lock();
+
+        if !state.products.contains_key(&product_id) {
+            return Err(NotFoundErrorModel::NotFoundError(
+                "Product not found".to_string(),
+            ));
+        }
+        if state
+            .items
+            .values()
+            .any(|item| item.product_id == product_id)
+        {
+            return Err(NotFoundErrorModel::DatabaseError(
+                "Failed to delete product: product still has items".to_string(),
+            ));
+        }
+
+        if let Some(product) = state.products.remove(&product_id) {
+            let before = to_whole_product_model(&product);
+            state.record_audit(
+                context,
+                AuditAction::Delete,
+                AuditEntity::Product,
+                product_id,
+                Some(&before),
+                None,
+            );
+            state.queue_events(DomainEventModel::for_product(Some(&before), None));
+        }
+        Ok(true)
+    }
+}
+
+#[async_trait::async_trait]
+impl ItemStore for InMemoryStore {
+    async fn create_item_in_db(
+        &self,
+        request: CreateItemModel,
+        context: &AuditContextModel,
+    ) -> Result<ItemModel, ErrorModel> {
+        self.lock().insert_item(request, context)
+    }
+
+    async fn delete_item_in_db(
+        &self,
+        item_id: i32,
+        context: &AuditContextModel,
+    ) -> Result<bool, NotFoundErrorModel> {
+        let mut state = self.lock();
+
+        match state.items.remove(&item_id) {
+            Some(item) => {
+                let before = to_item_model(item);
+                state.record_audit(
+                    context,
+                    AuditAction::Delete,
+                    AuditEntity::Item,
+                    item_id,
+                    Some(&before),
+                    None,
+                );
+                state.queue_events(DomainEventModel::for_item(Some(&before), None));
+                Ok(true)
+            }
+            None => Err(NotFoundErrorModel::NotFoundError(format!(
+                "Item with ID {} not found",
+                item_id
+            ))),
+        }
+    }
+
+    async fn update_item_in_db(
+        &self,
+        item_id: i32,
+        item_data: UpdateItemModel,
+        context: &AuditContextModel,
+    ) -> Result<ItemModel, NotFoundErrorModel> {
+        let mut state = self.lock();
+
+        match state.items.get_mut(&item_id) {
+            Some(item) => {
+                let before = to_item_model(item.clone());
+                if let Some(size) = item_data.size {
+                    item.size = size;
+                }
+                if let Some(color) = item_data.color {
+                    item.color = color;
+                }
+                if let Some(stock) = item_data.stock {
+                    item.stock = stock;
+                }
+
+                let after = to_item_model(item.clone());
+                state.record_audit(
+                    context,
+                    AuditAction::Update,
+                    AuditEntity::Item,
+                    item_id,
+                    Some(&before),
+                    Some(&after),
+                );
+                state.queue_events(DomainEventModel::for_item(Some(&before), Some(&after)));
+                Ok(after)
+            }
+            None => Err(NotFoundErrorModel::NotFoundError(format!(
+                "Item with ID {} not found",
+                item_id
+            ))),
+        }
+    }
+
+    async fn get_item_by_id_from_db(&self, item_id: i32) -> Result<ItemModel, NotFoundErrorModel> {
+        match self.lock().items.get(&item_id) {
+            Some(item) => Ok(to_item_model(item.clone())),
+            None => Err(NotFoundErrorModel::NotFoundError(
+                "Item not found".to_string(),
+            )),
+        }
+    }
+
+    async fn get_stock_summary_from_db(&self) -> Result<StockSummaryModel, ErrorModel> {
+        let state = self.lock();
+
+        Ok(StockSummaryModel {
+            total_items: state.items.len() as u64,
+            out_of_stock_items: state.items.values().filter(|item| item.stock <= 0).count() as u64,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditStore for InMemoryStore {
+    async fn get_audit_logs_from_db(
+        &self,
+        query: AuditQueryModel,
+    ) -> Result<Vec<AuditLogModel>, ErrorModel> {
+        Ok(self
+            .lock()
+            .audit_logs
+            .iter()
+            .rev()
+            .filter(|entry| {
+                query
+                    .entity
+                    .as_ref()
+                    .is_none_or(|entity| &entry.entity_type == entity)
+            })
+            .filter(|entry| query.id.is_none_or(|id| entry.entity_id == id))
+            .filter(|entry| query.from.is_none_or(|from| entry.created_at >= from))
+            .filter(|entry| query.to.is_none_or(|to| entry.created_at < to))
+            .filter(|entry| query.before_id.is_none_or(|id| entry.id < id))
+            .take(query.limit.unwrap_or(DEFAULT_AUDIT_LIMIT) as usize)
+            .cloned()
+            .map(to_audit_log_model)
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl OutboxStore for InMemoryStore {
+    async fn claim_pending_events_from_db(
+        &self,
+        limit: u64,
+        locked_until: NaiveDateTime,
+    ) -> Result<Vec<OutboxEventModel>, ErrorModel> {
+        let now = Utc::now().naive_utc();
+
+        Ok(self
+            .lock()
+            .outbox
+            .values_mut()
+            .filter(|event| event.sent_at.is_none() && event.available_at <= now)
+            .take(limit as usize)
+            .map(|event| {
+                let claimed = event.clone();
+                event.available_at = locked_until;
+                to_outbox_event_model(claimed)
+            })
+            .collect())
+    }
+
+    async fn mark_event_sent_in_db(&self, event_id: i32) -> Result<(), ErrorModel> {
+        if let Some(event) = self.lock().outbox.get_mut(&event_id) {
+            event.sent_at = Some(Utc::now().naive_utc());
+        }
+
+        Ok(())
+    }
+
+    async fn mark_event_failed_in_db(
+        &self,
+        event_id: i32,
+        attempts: i32,
+        error: String,
+        retry_at: NaiveDateTime,
+    ) -> Result<(), ErrorModel> {
+        if let Some(event) = self.lock().outbox.get_mut(&event_id) {
+            event.attempts = attempts;
+            event.last_error = Some(error);
+            event.available_at = retry_at;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookStore for InMemoryStore {
+    async fn create_webhook_in_db(
+        &self,
+        url: String,
+        event_types: Vec<String>,
+        secret: String,
+    ) -> Result<WebhookModel, ErrorModel> {
+        let mut state = self.lock();
+        let now = Utc::now().naive_utc();
+
+        state.last_webhook_id += 1;
+        let webhook = webhook_subscription::Model {
+            id: state.last_webhook_id,
+            url,
+            event_types: event_types.join(" "),
+            secret,
+            active: true,
+            created_at: now,
+            updated_at: now,
+        };
+        state.webhooks.insert(webhook.id, webhook.clone());
+
+        Ok(to_webhook_model(webhook))
+    }
+
+    async fn get_all_webhooks_from_db(&self) -> Result<Vec<WebhookModel>, ErrorModel> {
+        Ok(self
+            .lock()
+            .webhooks
+            .values()
+            .cloned()
+            .map(to_webhook_model)
+            .collect())
+    }
+
+    async fn get_webhook_by_id_from_db(
+        &self,
+        webhook_id: i32,
+    ) -> Result<WebhookModel, NotFoundErrorModel> {
+        match self.lock().webhooks.get(&webhook_id) {
+            Some(webhook) => Ok(to_webhook_model(webhook.clone())),
+            None => Err(NotFoundErrorModel::NotFoundError(format!(
+                "Webhook with ID {} not found",
+                webhook_id
+            ))),
+        }
+    }
+
+    async fn update_webhook_in_db(
+        &self,
+        webhook_id: i32,
+        webhook_data: UpdateWebhookModel,
+    ) -> Result<WebhookModel, NotFoundErrorModel> {
+        match self.lock().webhooks.get_mut(&webhook_id) {
+            Some(webhook) => {
+                if let Some(url) = webhook_data.url {
+                    webhook.url = url;
+                }
+                if let Some(event_types) = webhook_data.event_types {
+                    webhook.event_types = event_types.join(" ");
+                }
+                if let Some(secret) = webhook_data.secret {
+                    webhook.secret = secret;
+                }
+                if let Some(active) = webhook_data.active {
+                    webhook.active = active;
+                }
+                webhook.updated_at = Utc::now().naive_utc();
+
+                Ok(to_webhook_model(webhook.clone()))
+            }
+            None => Err(NotFoundErrorModel::NotFoundError(format!(
+                "Webhook with ID {} not found",
+                webhook_id
+            ))),
+        }
+    }
+
+    async fn delete_webhook_in_db(&self, webhook_id: i32) -> Result<bool, NotFoundErrorModel> {
+        let mut state = self.lock();
+
+        match state.webhooks.remove(&webhook_id) {
+            Some(_) => {
+                state
+                    .webhook_deliveries
+                    .retain(|_, delivery| delivery.subscription_id != webhook_id);
+                Ok(true)
+            }
+            None => Err(NotFoundErrorModel::NotFoundError(format!(
+                "Webhook with ID {} not found",
+                webhook_id
+            ))),
+        }
+    }
+
+    async fn queue_webhook_deliveries_in_db(
+        &self,
+        event: &OutboxEventModel,
+    ) -> Result<usize, ErrorModel> {
+        let mut state = self.lock();
+        let now = Utc::now().naive_utc();
+        let payload = serde_json::to_value(event).map_err(|err| {
+            ErrorModel::DatabaseError(format!("Failed to serialize event: {}", err))
+        })?;
+
+        let webhook_ids: Vec<i32> = state
+            .webhooks
+            .values()
+            .filter(|webhook| {
+                webhook.active
+                    && webhook
+                        .event_types
+                        .split_whitespace()
+                        .any(|event_type| event_type == event.event_type)
+            })
+            .filter(|webhook| {
+                !state.webhook_deliveries.values().any(|delivery| {
+                    delivery.subscription_id == webhook.id && delivery.event_id == event.id
+                })
+            })
+            .map(|webhook| webhook.id)
+            .collect();
+
+        for webhook_id in &webhook_ids {
+            state.last_webhook_delivery_id += 1;
+            let delivery = webhook_delivery::Model {
+                id: state.last_webhook_delivery_id,
+                subscription_id: *webhook_id,
+                event_id: event.id,
+                event_type: event.event_type.clone(),
+                payload: payload.clone(),
+                status: WebhookDeliveryStatus::Pending.as_str().to_string(),
+                attempts: 0,
+                response_status: None,
+                last_error: None,
+                next_attempt_at: now,
+                created_at: now,
+                delivered_at: None,
+            };
+            state.webhook_deliveries.insert(delivery.id, delivery);
+        }
+
+        Ok(webhook_ids.len())
+    }
+
+    async fn claim_due_webhook_deliveries_from_db(
+        &self,
+        limit: u64,
+        locked_until: NaiveDateTime,
+    ) -> Result<Vec<WebhookDeliveryModel>, ErrorModel> {
+        let now = Utc::now().naive_utc();
+
+        Ok(self
+            .lock()
+            .webhook_deliveries
+            .values_mut()
+            .filter(|delivery| {
+                delivery.status == WebhookDeliveryStatus::Pending.as_str()
+                    && delivery.next_attempt_at <= now
+            })
+            .take(limit as usize)
+            .map(|delivery| {
+                let claimed = delivery.clone();
+                delivery.next_attempt_at = locked_until;
+                to_webhook_delivery_model(claimed)
+            })
+            .collect())
+    }
+
+    async fn update_webhook_delivery_in_db(
+        &self,
+        delivery: WebhookDeliveryModel,
+    ) -> Result<(), ErrorModel> {
+        if let Some(stored) = self.lock().webhook_deliveries.get_mut(&delivery.id) {
+            stored.status = delivery.status;
+            stored.attempts = delivery.attempts;
+            stored.response_status = delivery.response_status;
+            stored.last_error = delivery.last_error;
+            stored.next_attempt_at = delivery.next_attempt_at;
+            stored.delivered_at = delivery.delivered_at;
+        }
+
+        Ok(())
+    }
+
+    async fn get_webhook_deliveries_from_db(
+        &self,
+        webhook_id: i32,
+        limit: u64,
+    ) -> Result<Vec<WebhookDeliveryModel>, ErrorModel> {
+        Ok(self
+            .lock()
+            .webhook_deliveries
+            .values()
+            .rev()
+            .filter(|delivery| delivery.subscription_id == webhook_id)
+            .take(limit as usize)
+            .cloned()
+            .map(to_webhook_delivery_model)
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiKeyStore for InMemoryStore {
+    async fn create_api_key_in_db(
+        &self,
+        name: String,
+        prefix: String,
+        secret_hash: String,
+        scopes: Vec<String>,
+        expires_at: Option<NaiveDateTime>,
+    ) -> Result<ApiKeyModel, ErrorModel> {
+        let mut state = self.lock();
+
+        if state
+            .api_keys
+            .values()
+            .any(|api_key| api_key.prefix == prefix)
+        {
+            return Err(ErrorModel::DatabaseError(
+                "Failed to create API key: duplicate prefix".to_string(),
+            ));
+        }
+
+        state.last_api_key_id += 1;
+        let api_key = api_key::Model {
+            id: state.last_api_key_id,
+            name,
+            prefix,
+            secret_hash,
+            scopes: scopes.join(" "),
+            expires_at,
+            last_used_at: None,
+            revoked_at: None,
+            created_at: Utc::now().naive_utc(),
+        };
+        state.api_keys.insert(api_key.id, api_key.clone());
+
+        Ok(to_api_key_model(api_key))
+    }
+
+    async fn get_all_api_keys_from_db(&self) -> Result<Vec<ApiKeyModel>, ErrorModel> {
+        Ok(self
+            .lock()
+            .api_keys
+            .values()
+            .cloned()
+            .map(to_api_key_model)
+            .collect())
+    }
+
+    async fn get_api_key_by_prefix_from_db(
+        &self,
+        prefix: &str,
+    ) -> Result<ApiKeyModel, NotFoundErrorModel> {
+        match self
+            .lock()
+            .api_keys
+            .values()
+            .find(|api_key| api_key.prefix == prefix)
+        {
+            Some(api_key) => Ok(to_api_key_model(api_key.clone())),
+            None => Err(NotFoundErrorModel::NotFoundError(
+                "API key not found".to_string(),
+            )),
+        }
+    }
+
+    async fn rotate_api_key_in_db(
+        &self,
+        api_key_id: i32,
+        prefix: String,
+        secret_hash: String,
+    ) -> Result<ApiKeyModel, NotFoundErrorModel> {
+        self.lock().update_api_key(api_key_id, |api_key| {
+            api_key.prefix = prefix;
+            api_key.secret_hash = secret_hash;
+        })
+    }
+
+    async fn revoke_api_key_in_db(
+        &self,
+        api_key_id: i32,
+    ) -> Result<ApiKeyModel, NotFoundErrorModel> {
+        self.lock().update_api_key(api_key_id, |api_key| {
+            api_key.revoked_at = Some(Utc::now().naive_utc());
+        })
+    }
+
+    async fn touch_api_key_in_db(&self, api_key_id: i32) -> Result<(), ErrorModel> {
+        self.lock()
+            .update_api_key(api_key_id, |api_key| {
+                api_key.last_used_at = Some(Utc::now().naive_utc());
+            })
+            .map(|_| ())
+            .map_err(|_| ErrorModel::DatabaseError("Failed to record API key use".to_string()))
+    }
+}
+
+#[async_trait::async_trait]
+impl IdempotencyStore for InMemoryStore {
+    async fn get_idempotency_key_from_db(
+        &self,
+        subject: &str,
+        key: &str,
+    ) -> Result<Option<IdempotencyRecordModel>, ErrorModel> {
+        Ok(self
+            .lock()
+            .idempotency_keys
+            .get(&(subject.to_string(), key.to_string()))
+            .cloned()
+            .map(to_idempotency_record_model))
+    }
+
+    async fn create_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+        fingerprint: &str,
+        locked_until: NaiveDateTime,
+    ) -> Result<bool, ErrorModel> {
+        let mut state = self.lock();
+        let scoped_key = (subject.to_string(), key.to_string());
+
+        if state.idempotency_keys.contains_key(&scoped_key) {
+            return Ok(false);
+        }
+
+        state.last_idempotency_key_id += 1;
+        let record = idempotency_key::Model {
+            id: state.last_idempotency_key_id,
+            subject: subject.to_string(),
+            key: key.to_string(),
+            fingerprint: fingerprint.to_string(),
+            status_code: None,
+            response_body: None,
+            created_at: Utc::now().naive_utc(),
+            locked_until: Some(locked_until),
+        };
+        state.idempotency_keys.insert(scoped_key, record);
+
+        Ok(true)
+    }
+
+    async fn take_over_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+        now: NaiveDateTime,
+        locked_until: NaiveDateTime,
+    ) -> Result<bool, ErrorModel> {
+        let mut state = self.lock();
+        match state
+            .idempotency_keys
+            .get_mut(&(subject.to_string(), key.to_string()))
+        {
+            Some(record)
+                if record.status_code.is_none()
+                    && record.locked_until.is_none_or(|until| until < now) =>
+            {
+                record.locked_until = Some(locked_until);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn complete_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+        status_code: u16,
+        response_body: String,
+    ) -> Result<(), ErrorModel> {
+        if let Some(record) = self
+            .lock()
+            .idempotency_keys
+            .get_mut(&(subject.to_string(), key.to_string()))
+        {
+            record.status_code = Some(status_code as i32);
+            record.response_body = Some(response_body);
+            record.locked_until = None;
+        }
+
+        Ok(())
+    }
+
+    async fn delete_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+    ) -> Result<(), ErrorModel> {
+        self.lock()
+            .idempotency_keys
+            .remove(&(subject.to_string(), key.to_string()));
+
+        Ok(())
+    }
+
+    async fn release_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+        locked_until: NaiveDateTime,
+    ) -> Result<(), ErrorModel> {
+        let mut state = self.lock();
+        let id = (subject.to_string(), key.to_string());
+        if state.idempotency_keys.get(&id).is_some_and(|record| {
+            record.status_code.is_none() && record.locked_until == Some(locked_until)
+        }) {
+            state.idempotency_keys.remove(&id);
+        }
+
+        Ok(())
+    }
+
+    async fn purge_idempotency_keys_in_db(
+        &self,
+        created_before: NaiveDateTime,
+    ) -> Result<u64, ErrorModel> {
+        let mut state = self.lock();
+        let count = state.idempotency_keys.len();
+        state
+            .idempotency_keys
+            .retain(|_, record| record.created_at >= created_before);
+
+        Ok((count - state.idempotency_keys.len()) as u64)
+    }
+}
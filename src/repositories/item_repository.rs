@@ -1,22 +1,53 @@
-use sea_orm::{ActiveValue::NotSet, DatabaseConnection, EntityTrait};
+use sea_orm::{ActiveValue::NotSet, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter};
 use sea_orm::{
     ActiveModelTrait,Set,
 };
+use tracing::instrument;
 
-use crate::{entities::item, models::{item_model::{CreateItemModel, ItemModel, UpdateItemModel}, ErrorModel, NotFoundErrorModel}};
+use crate::{entities::item, models::{audit_model::{AuditAction, AuditContextModel, AuditEntity}, event_model::DomainEventModel, item_model::{CreateItemModel, ItemModel, StockSummaryModel, UpdateItemModel}, ErrorModel, NotFoundErrorModel}};
+
+use crate::metrics::time_query;
+
+use super::{audit_repository::AuditRepository, outbox_repository::OutboxRepository, store::ItemStore, unit_of_work::UnitOfWork};
 
 
 #[derive(Clone)]
-pub struct ItemRepository {
-    db: DatabaseConnection,
+pub struct ItemRepository<C = DatabaseConnection> {
+    db: C,
 }
 
 impl ItemRepository {
-    pub fn new(db: DatabaseConnection) -> Self {
+    pub async fn begin(&self) -> Result<UnitOfWork, sea_orm::DbErr> {
+        UnitOfWork::begin(&self.db).await
+    }
+}
+
+// Every change is audited and its events queued on the same connection; the
+// `ItemStore` impl runs each one in its own transaction.
+impl<C: ConnectionTrait + Clone> ItemRepository<C> {
+    pub fn new(db: C) -> Self {
         Self { db }
     }
 
-    pub async fn create_item_in_db(&self, request: CreateItemModel) -> Result<ItemModel, ErrorModel> {
+    /// Audits a change and queues its domain events in the outbox.
+    async fn record_change(
+        &self,
+        context: &AuditContextModel,
+        action: AuditAction,
+        item_id: i32,
+        before: Option<&ItemModel>,
+        after: Option<&ItemModel>,
+    ) -> Result<(), sea_orm::DbErr> {
+        AuditRepository::new(self.db.clone())
+            .insert_audit_log_in_db(context, action, AuditEntity::Item, item_id, before, after)
+            .await?;
+        OutboxRepository::new(self.db.clone())
+            .insert_events_in_db(&DomainEventModel::for_item(before, after))
+            .await
+    }
+
+    #[instrument(skip(self, request, context), fields(product_id = request.product_id))]
+    pub async fn create_item_in_db(&self, request: CreateItemModel, context: &AuditContextModel) -> Result<ItemModel, ErrorModel> {
         let item_model = item::ActiveModel {
             product_id:Set(request.product_id),
             color: Set(request.color),
@@ -25,27 +56,49 @@ impl ItemRepository {
             ..Default::default()
         };
 
-        match item_model.insert(&self.db).await {
-            Ok(inserted_item) => Ok(ItemModel {
+        let item = match item_model.insert(&self.db).await {
+            Ok(inserted_item) => ItemModel {
                 id:inserted_item.id,
                 product_id: inserted_item.product_id,
                 color: inserted_item.color,
                 stock: inserted_item.stock,
                 size: inserted_item.size,
-            }),
-            Err(_) => Err(ErrorModel::DatabaseError(
+            },
+            Err(_) => return Err(ErrorModel::DatabaseError(
                 "Failed to create item".to_string(),
             )),
+        };
+
+        match self
+            .record_change(context, AuditAction::Create, item.id, None, Some(&item))
+            .await
+        {
+            Ok(_) => Ok(item),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to record item change: {}",
+                err
+            ))),
         }
     }
 
-    pub async fn delete_item_in_db(&self, item_id: i32) -> Result<bool, NotFoundErrorModel> {
+    #[instrument(skip(self, context))]
+    pub async fn delete_item_in_db(&self, item_id: i32, context: &AuditContextModel) -> Result<bool, NotFoundErrorModel> {
         match self.find_item(item_id).await {
-            Ok(item) => {
+            Ok(Some(item)) => {
                 match item::Entity::delete_by_id(item_id).exec(&self.db).await {
                     Ok(delete_result) => {
                         if delete_result.rows_affected > 0 {
-                            Ok(true)
+                            let before = to_item_model(item);
+                            match self
+                                .record_change(context, AuditAction::Delete, item_id, Some(&before), None)
+                                .await
+                            {
+                                Ok(_) => Ok(true),
+                                Err(err) => Err(NotFoundErrorModel::DatabaseError(format!(
+                                    "Failed to record item change: {}",
+                                    err
+                                ))),
+                            }
                         } else {
                             Ok(false)
                         }
@@ -56,8 +109,12 @@ impl ItemRepository {
                     ))),
                 }
             }
+            Ok(None) => Err(NotFoundErrorModel::NotFoundError(format!(
+                "Item with ID {} not found",
+                item_id
+            ))),
             Err(err) => Err(NotFoundErrorModel::DatabaseError(format!(
-                "Failed to update product: {}",
+                "Failed to retrieve item: {}",
                 err
             )))
         }
@@ -65,13 +122,16 @@ impl ItemRepository {
         
     }
 
+    #[instrument(skip(self, item_data, context))]
     pub async fn update_item_in_db(
         &self,
         item_id: i32,
         item_data: UpdateItemModel,
+        context: &AuditContextModel,
     ) -> Result<ItemModel, NotFoundErrorModel> {
         match self.find_item(item_id).await {
             Ok(Some(item)) => {
+                let before = to_item_model(item.clone());
                 let mut updated_item: item::ActiveModel = item.into();
     
                 updated_item.size = match item_data.size {
@@ -87,18 +147,23 @@ impl ItemRepository {
                     None => NotSet,
                 };
     
-                match updated_item.update(&self.db).await {
-                    Ok(updated_item) => Ok(ItemModel {
-                        id: updated_item.id,
-                        product_id: updated_item.product_id,
-                        color: updated_item.color,
-                        stock: updated_item.stock,
-                        size: updated_item.size,
-                    }),
-                    Err(err) => Err(NotFoundErrorModel::DatabaseError(format!(
+                let after = match updated_item.update(&self.db).await {
+                    Ok(updated_item) => to_item_model(updated_item),
+                    Err(err) => return Err(NotFoundErrorModel::DatabaseError(format!(
                         "Failed to update item: {}",
                         err
                     ))),
+                };
+
+                match self
+                    .record_change(context, AuditAction::Update, item_id, Some(&before), Some(&after))
+                    .await
+                {
+                    Ok(_) => Ok(after),
+                    Err(err) => Err(NotFoundErrorModel::DatabaseError(format!(
+                        "Failed to record item change: {}",
+                        err
+                    ))),
                 }
             }
             Ok(None) => Err(NotFoundErrorModel::NotFoundError(format!(
@@ -113,6 +178,7 @@ impl ItemRepository {
     }
     
 
+    #[instrument(skip(self))]
     pub async fn get_item_by_id_from_db(&self,item_id: i32) -> Result<ItemModel, NotFoundErrorModel> {
         match self.find_item(item_id).await {
             Ok(Some(item)) => Ok(ItemModel {
@@ -133,6 +199,26 @@ impl ItemRepository {
         
     }
 
+    #[instrument(skip(self))]
+    pub async fn get_stock_summary_from_db(&self) -> Result<StockSummaryModel, ErrorModel> {
+        let total_items = item::Entity::find().count(&self.db).await;
+        let out_of_stock_items = item::Entity::find()
+            .filter(item::Column::Stock.lte(0))
+            .count(&self.db)
+            .await;
+
+        match (total_items, out_of_stock_items) {
+            (Ok(total_items), Ok(out_of_stock_items)) => Ok(StockSummaryModel {
+                total_items,
+                out_of_stock_items,
+            }),
+            (Err(err), _) | (_, Err(err)) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to count items: {}",
+                err
+            ))),
+        }
+    }
+
     pub async fn find_item(
         &self,
         item_id: i32,
@@ -140,4 +226,84 @@ impl ItemRepository {
         item::Entity::find_by_id(item_id).one(&self.db).await
     }
     
-}
\ No newline at end of file
+}
+
+fn to_item_model(item: item::Model) -> ItemModel {
+    ItemModel {
+        id: item.id,
+        product_id: item.product_id,
+        color: item.color,
+        stock: item.stock,
+        size: item.size,
+    }
+}
+
+#[async_trait::async_trait]
+impl ItemStore for ItemRepository {
+    async fn create_item_in_db(
+        &self,
+        request: CreateItemModel,
+        context: &AuditContextModel,
+    ) -> Result<ItemModel, ErrorModel> {
+        time_query("item", "create_item_in_db", async {
+            let uow = self.begin().await.map_err(|err| {
+                ErrorModel::DatabaseError(format!("Failed to start transaction: {}", err))
+            })?;
+            let result = uow.item_repository().create_item_in_db(request, context).await;
+            uow.finish(result, ErrorModel::DatabaseError).await
+        })
+        .await
+    }
+
+    async fn delete_item_in_db(
+        &self,
+        item_id: i32,
+        context: &AuditContextModel,
+    ) -> Result<bool, NotFoundErrorModel> {
+        time_query("item", "delete_item_in_db", async {
+            let uow = self.begin().await.map_err(|err| {
+                NotFoundErrorModel::DatabaseError(format!("Failed to start transaction: {}", err))
+            })?;
+            let result = uow.item_repository().delete_item_in_db(item_id, context).await;
+            uow.finish(result, NotFoundErrorModel::DatabaseError).await
+        })
+        .await
+    }
+
+    async fn update_item_in_db(
+        &self,
+        item_id: i32,
+        item_data: UpdateItemModel,
+        context: &AuditContextModel,
+    ) -> Result<ItemModel, NotFoundErrorModel> {
+        time_query("item", "update_item_in_db", async {
+            let uow = self.begin().await.map_err(|err| {
+                NotFoundErrorModel::DatabaseError(format!("Failed to start transaction: {}", err))
+            })?;
+            let result = uow
+                .item_repository()
+                .update_item_in_db(item_id, item_data, context)
+                .await;
+            uow.finish(result, NotFoundErrorModel::DatabaseError).await
+        })
+        .await
+    }
+
+    async fn get_item_by_id_from_db(&self, item_id: i32) -> Result<ItemModel, NotFoundErrorModel> {
+        time_query(
+            "item",
+            "get_item_by_id_from_db",
+            ItemRepository::get_item_by_id_from_db(self, item_id),
+        )
+        .await
+    }
+
+    async fn get_stock_summary_from_db(&self) -> Result<StockSummaryModel, ErrorModel> {
+        time_query(
+            "item",
+            "get_stock_summary_from_db",
+            ItemRepository::get_stock_summary_from_db(self),
+        )
+        .await
+    }
+}
@@ -1,2 +1,10 @@
+pub mod api_key_repository;
+pub mod audit_repository;
+pub mod idempotency_repository;
 pub mod product_repository;
-pub mod item_repository;
\ No newline at end of file
+pub mod item_repository;
+pub mod outbox_repository;
+pub mod webhook_repository;
+pub mod unit_of_work;
+pub mod store;
+pub mod in_memory;
\ No newline at end of file
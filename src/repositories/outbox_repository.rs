@@ -0,0 +1,192 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{
+    sea_query::{Expr, LockBehavior, LockType},
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use tracing::instrument;
+
+use crate::{
+    entities::outbox,
+    metrics::time_query,
+    models::{
+        event_model::{DomainEventModel, OutboxEventModel},
+        ErrorModel,
+    },
+};
+
+use super::store::OutboxStore;
+
+#[derive(Clone)]
+pub struct OutboxRepository<C = DatabaseConnection> {
+    db: C,
+}
+
+impl<C: ConnectionTrait> OutboxRepository<C> {
+    pub fn new(db: C) -> Self {
+        Self { db }
+    }
+
+    /// Queues events for delivery; run it on the connection of the change
+    /// they describe so both are committed or rolled back together.
+    #[instrument(skip(self, events), fields(events = events.len()))]
+    pub async fn insert_events_in_db(&self, events: &[DomainEventModel]) -> Result<(), DbErr> {
+        let now = Utc::now().naive_utc();
+
+        for event in events {
+            let record = outbox::ActiveModel {
+                event_type: Set(event.event_type().to_string()),
+                product_id: Set(event.product_id()),
+                payload: Set(event.payload()),
+                created_at: Set(now),
+                available_at: Set(now),
+                attempts: Set(0),
+                ..Default::default()
+            };
+            record.insert(&self.db).await?;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn mark_event_sent_in_db(&self, event_id: i32) -> Result<(), ErrorModel> {
+        let event = outbox::ActiveModel {
+            id: Set(event_id),
+            sent_at: Set(Some(Utc::now().naive_utc())),
+            ..Default::default()
+        };
+
+        match event.update(&self.db).await {
+            Ok(_) => Ok(()),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to mark event sent: {}",
+                err
+            ))),
+        }
+    }
+
+    #[instrument(skip(self, error))]
+    pub async fn mark_event_failed_in_db(
+        &self,
+        event_id: i32,
+        attempts: i32,
+        error: String,
+        retry_at: NaiveDateTime,
+    ) -> Result<(), ErrorModel> {
+        let event = outbox::ActiveModel {
+            id: Set(event_id),
+            attempts: Set(attempts),
+            last_error: Set(Some(error)),
+            available_at: Set(retry_at),
+            ..Default::default()
+        };
+
+        match event.update(&self.db).await {
+            Ok(_) => Ok(()),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to record event failure: {}",
+                err
+            ))),
+        }
+    }
+}
+
+impl OutboxRepository {
+    #[instrument(skip(self))]
+    pub async fn claim_pending_events_from_db(
+        &self,
+        limit: u64,
+        locked_until: NaiveDateTime,
+    ) -> Result<Vec<OutboxEventModel>, ErrorModel> {
+        match self.claim_pending_events(limit, locked_until).await {
+            Ok(events) => Ok(events.into_iter().map(to_outbox_event_model).collect()),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to claim pending events: {}",
+                err
+            ))),
+        }
+    }
+
+    /// Reads the due events and moves them out of reach of other dispatchers
+    /// in one transaction. Postgres skips rows another transaction is
+    /// claiming; SQLite ignores the lock, as it runs one writer at a time.
+    async fn claim_pending_events(
+        &self,
+        limit: u64,
+        locked_until: NaiveDateTime,
+    ) -> Result<Vec<outbox::Model>, DbErr> {
+        let txn = self.db.begin().await?;
+
+        let events = outbox::Entity::find()
+            .filter(outbox::Column::SentAt.is_null())
+            .filter(outbox::Column::AvailableAt.lte(Utc::now().naive_utc()))
+            .order_by_asc(outbox::Column::Id)
+            .limit(limit)
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+            .all(&txn)
+            .await?;
+
+        if !events.is_empty() {
+            outbox::Entity::update_many()
+                .col_expr(outbox::Column::AvailableAt, Expr::value(locked_until))
+                .filter(outbox::Column::Id.is_in(events.iter().map(|event| event.id)))
+                .exec(&txn)
+                .await?;
+        }
+
+        txn.commit().await?;
+        Ok(events)
+    }
+}
+
+pub(crate) fn to_outbox_event_model(event: outbox::Model) -> OutboxEventModel {
+    OutboxEventModel {
+        id: event.id,
+        event_type: event.event_type,
+        product_id: event.product_id,
+        payload: event.payload,
+        created_at: event.created_at,
+        attempts: event.attempts,
+    }
+}
+
+#[async_trait::async_trait]
+impl OutboxStore for OutboxRepository {
+    async fn claim_pending_events_from_db(
+        &self,
+        limit: u64,
+        locked_until: NaiveDateTime,
+    ) -> Result<Vec<OutboxEventModel>, ErrorModel> {
+        time_query(
+            "outbox",
+            "claim_pending_events_from_db",
+            OutboxRepository::claim_pending_events_from_db(self, limit, locked_until),
+        )
+        .await
+    }
+
+    async fn mark_event_sent_in_db(&self, event_id: i32) -> Result<(), ErrorModel> {
+        time_query(
+            "outbox",
+            "mark_event_sent_in_db",
+            OutboxRepository::mark_event_sent_in_db(self, event_id),
+        )
+        .await
+    }
+
+    async fn mark_event_failed_in_db(
+        &self,
+        event_id: i32,
+        attempts: i32,
+        error: String,
+        retry_at: NaiveDateTime,
+    ) -> Result<(), ErrorModel> {
+        time_query(
+            "outbox",
+            "mark_event_failed_in_db",
+            OutboxRepository::mark_event_failed_in_db(self, event_id, attempts, error, retry_at),
+        )
+        .await
+    }
+}
@@ -1,13 +1,16 @@
 use chrono::{NaiveDateTime, Utc};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
     ModelTrait, QueryFilter, Set,
 };
+use tracing::instrument;
 
 use crate::{
     entities::{item, product},
     models::{
-        item_model::ItemModel,
+        audit_model::{AuditAction, AuditContextModel, AuditEntity},
+        event_model::DomainEventModel,
+        item_model::{CreateItemModel, ItemModel},
         product_model::{
             CreateProductModal, ProductItemModel, UpdateProductModal, WholeProductModel,
         },
@@ -15,25 +18,135 @@ use crate::{
     },
 };
 
+use crate::metrics::time_query;
+
+use super::{
+    audit_repository::AuditRepository, outbox_repository::OutboxRepository, store::ProductStore,
+    unit_of_work::UnitOfWork,
+};
+
 #[derive(Clone)]
-pub struct ProductRepository{
-    db:DatabaseConnection,
+pub struct ProductRepository<C = DatabaseConnection>{
+    db:C,
 }
 
 impl ProductRepository {
-    pub fn new(db: DatabaseConnection) -> Self {
-        ProductRepository { db }
+    pub async fn begin(&self) -> Result<UnitOfWork, sea_orm::DbErr> {
+        UnitOfWork::begin(&self.db).await
     }
 
+    #[instrument(skip(self, request, context), fields(items = request.items.len()))]
     pub async fn create_product_in_db(
         &self,
         request: CreateProductModal,
+        context: &AuditContextModel,
+    ) -> Result<ProductItemModel, ErrorModel> {
+        let uow = match self.begin().await {
+            Ok(uow) => uow,
+            Err(err) => {
+                return Err(ErrorModel::DatabaseError(format!(
+                    "Failed to start transaction: {}",
+                    err
+                )))
+            }
+        };
+
+        let product = match uow
+            .product_repository()
+            .insert_product_in_db(request.name, request.description)
+            .await
+        {
+            Ok(product) => product,
+            Err(err) => {
+                let _ = uow.rollback().await;
+                return Err(err);
+            }
+        };
+
+        if let Err(err) = uow
+            .product_repository()
+            .record_change(context, AuditAction::Create, product.id, None, Some(&product))
+            .await
+        {
+            let _ = uow.rollback().await;
+            return Err(ErrorModel::DatabaseError(format!(
+                "Failed to record product change: {}",
+                err
+            )));
+        }
+
+        let mut items = Vec::with_capacity(request.items.len());
+        for item_data in request.items {
+            let created = uow
+                .item_repository()
+                .create_item_in_db(CreateItemModel {
+                    id: None,
+                    product_id: product.id,
+                    color: item_data.color,
+                    stock: item_data.stock,
+                    size: item_data.size,
+                }, context)
+                .await;
+
+            match created {
+                Ok(item) => items.push(item),
+                Err(err) => {
+                    let _ = uow.rollback().await;
+                    return Err(err);
+                }
+            }
+        }
+
+        match uow.commit().await {
+            Ok(_) => Ok(ProductItemModel {
+                id: product.id,
+                name: product.name,
+                description: product.description,
+                items,
+            }),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to commit product: {}",
+                err
+            ))),
+        }
+    }
+}
+
+// Every change is audited and its events queued on the same connection; the
+// `ProductStore` impl runs each one in its own transaction.
+impl<C: ConnectionTrait + Clone> ProductRepository<C> {
+    pub fn new(db: C) -> Self {
+        ProductRepository { db }
+    }
+
+    /// Audits a change and queues its domain events in the outbox.
+    async fn record_change(
+        &self,
+        context: &AuditContextModel,
+        action: AuditAction,
+        product_id: i32,
+        before: Option<&WholeProductModel>,
+        after: Option<&WholeProductModel>,
+    ) -> Result<(), sea_orm::DbErr> {
+        AuditRepository::new(self.db.clone())
+            .insert_audit_log_in_db(context, action, AuditEntity::Product, product_id, before, after)
+            .await?;
+        OutboxRepository::new(self.db.clone())
+            .insert_events_in_db(&DomainEventModel::for_product(before, after))
+            .await
+    }
+
+    #[instrument(skip(self, name, description))]
+    pub async fn insert_product_in_db(
+        &self,
+        name: String,
+        description: Option<String>,
     ) -> Result<WholeProductModel, ErrorModel> {
         let now: NaiveDateTime = Utc::now().naive_utc();
 
         let product_model = product::ActiveModel {
-            name: Set(request.name.to_owned()),
-            description: Set(request.description.to_owned()),
+            name: Set(name),
+            description: Set(description),
             created_at: Set(now),
             updated_at: Set(now),
             ..Default::default()
@@ -53,6 +166,7 @@ impl ProductRepository {
         }
     }
 
+    #[instrument(skip(self))]
     pub async fn get_all_products_from_db(&self) -> Result<Vec<ProductItemModel>, ErrorModel> {
         match product::Entity::find()
             .find_with_related(item::Entity)
@@ -87,10 +201,12 @@ impl ProductRepository {
         }
     }
 
+    #[instrument(skip(self, product_data, context))]
     pub async fn update_product_in_db(
         &self,
         product_id: i32,
         product_data: UpdateProductModal,
+        context: &AuditContextModel,
     ) -> Result<WholeProductModel, NotFoundErrorModel> {
         let now: NaiveDateTime = Utc::now().naive_utc();
 
@@ -101,6 +217,8 @@ impl ProductRepository {
 
         match product_result {
             Ok(Some(existing_product)) => {
+                let before = to_whole_product_model(existing_product.clone());
+
                 // Convert the fetched model into an ActiveModel for update
                 let mut updated_product: product::ActiveModel = existing_product.clone().into();
 
@@ -115,13 +233,19 @@ impl ProductRepository {
 
                 match updated_product.update(&self.db).await {
                     Ok(_) => match self.find_product(product_id).await {
-                        Ok(Some(updated_product)) => Ok(WholeProductModel {
-                            id: updated_product.id,
-                            name: updated_product.name,
-                            description: updated_product.description,
-                            created_at: updated_product.created_at,
-                            updated_at: updated_product.updated_at,
-                        }),
+                        Ok(Some(updated_product)) => {
+                            let after = to_whole_product_model(updated_product);
+                            match self
+                                .record_change(context, AuditAction::Update, product_id, Some(&before), Some(&after))
+                                .await
+                            {
+                                Ok(_) => Ok(after),
+                                Err(err) => Err(NotFoundErrorModel::DatabaseError(format!(
+                                    "Failed to record product change: {}",
+                                    err
+                                ))),
+                            }
+                        }
                         Ok(None) => Err(NotFoundErrorModel::NotFoundError(
                             "Product not found after update".to_string(),
                         )),
@@ -154,13 +278,26 @@ impl ProductRepository {
     }
 
    
-    pub async fn delete_product_in_db(&self, product_id: i32) -> Result<bool, NotFoundErrorModel> {
+    #[instrument(skip(self, context))]
+    pub async fn delete_product_in_db(&self, product_id: i32, context: &AuditContextModel) -> Result<bool, NotFoundErrorModel> {
         let product_result = self.find_product(product_id)
             .await;
 
         match product_result {
-            Ok(Some(existing_product)) => match existing_product.delete(&self.db).await {
-                Ok(_) => Ok(true),
+            Ok(Some(existing_product)) => match existing_product.clone().delete(&self.db).await {
+                Ok(_) => {
+                    let before = to_whole_product_model(existing_product);
+                    match self
+                        .record_change(context, AuditAction::Delete, product_id, Some(&before), None)
+                        .await
+                    {
+                        Ok(_) => Ok(true),
+                        Err(err) => Err(NotFoundErrorModel::DatabaseError(format!(
+                            "Failed to record product change: {}",
+                            err
+                        ))),
+                    }
+                }
                 Err(err) => Err(NotFoundErrorModel::DatabaseError(format!(
                     "Failed to delete product: {}",
                     err
@@ -175,4 +312,76 @@ impl ProductRepository {
             ))),
         }
     }
-}
\ No newline at end of file
+}
+
+fn to_whole_product_model(product: product::Model) -> WholeProductModel {
+    WholeProductModel {
+        id: product.id,
+        name: product.name,
+        description: product.description,
+        created_at: product.created_at,
+        updated_at: product.updated_at,
+    }
+}
+
+#[async_trait::async_trait]
+impl ProductStore for ProductRepository {
+    async fn create_product_in_db(
+        &self,
+        request: CreateProductModal,
+        context: &AuditContextModel,
+    ) -> Result<ProductItemModel, ErrorModel> {
+        time_query(
+            "product",
+            "create_product_in_db",
+            ProductRepository::create_product_in_db(self, request, context),
+        )
+        .await
+    }
+
+    async fn get_all_products_from_db(&self) -> Result<Vec<ProductItemModel>, ErrorModel> {
+        time_query(
+            "product",
+            "get_all_products_from_db",
+            ProductRepository::get_all_products_from_db(self),
+        )
+        .await
+    }
+
+    async fn update_product_in_db(
+        &self,
+        product_id: i32,
+        product_data: UpdateProductModal,
+        context: &AuditContextModel,
+    ) -> Result<WholeProductModel, NotFoundErrorModel> {
+        time_query("product", "update_product_in_db", async {
+            let uow = self.begin().await.map_err(|err| {
+                NotFoundErrorModel::DatabaseError(format!("Failed to start transaction: {}", err))
+            })?;
+            let result = uow
+                .product_repository()
+                .update_product_in_db(product_id, product_data, context)
+                .await;
+            uow.finish(result, NotFoundErrorModel::DatabaseError).await
+        })
+        .await
+    }
+
+    async fn delete_product_in_db(
+        &self,
+        product_id: i32,
+        context: &AuditContextModel,
+    ) -> Result<bool, NotFoundErrorModel> {
+        time_query("product", "delete_product_in_db", async {
+            let uow = self.begin().await.map_err(|err| {
+                NotFoundErrorModel::DatabaseError(format!("Failed to start transaction: {}", err))
+            })?;
+            let result = uow
+                .product_repository()
+                .delete_product_in_db(product_id, context)
+                .await;
+            uow.finish(result, NotFoundErrorModel::DatabaseError).await
+        })
+        .await
+    }
+}
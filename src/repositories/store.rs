@@ -0,0 +1,249 @@
+use chrono::NaiveDateTime;
+
+use crate::models::{
+    api_key_model::ApiKeyModel,
+    audit_model::{AuditContextModel, AuditLogModel, AuditQueryModel},
+    event_model::OutboxEventModel,
+    idempotency_model::IdempotencyRecordModel,
+    item_model::{CreateItemModel, ItemModel, StockSummaryModel, UpdateItemModel},
+    product_model::{CreateProductModal, ProductItemModel, UpdateProductModal, WholeProductModel},
+    webhook_model::{UpdateWebhookModel, WebhookDeliveryModel, WebhookModel},
+    ErrorModel, NotFoundErrorModel,
+};
+
+/// Persistence operations the product service depends on.
+#[async_trait::async_trait]
+pub trait ProductStore: Send + Sync {
+    /// Creates the product together with its items as a single atomic write.
+    async fn create_product_in_db(
+        &self,
+        request: CreateProductModal,
+        context: &AuditContextModel,
+    ) -> Result<ProductItemModel, ErrorModel>;
+
+    async fn get_all_products_from_db(&self) -> Result<Vec<ProductItemModel>, ErrorModel>;
+
+    async fn update_product_in_db(
+        &self,
+        product_id: i32,
+        product_data: UpdateProductModal,
+        context: &AuditContextModel,
+    ) -> Result<WholeProductModel, NotFoundErrorModel>;
+
+    async fn delete_product_in_db(
+        &self,
+        product_id: i32,
+        context: &AuditContextModel,
+    ) -> Result<bool, NotFoundErrorModel>;
+}
+
+/// Persistence operations the item service depends on.
+#[async_trait::async_trait]
+pub trait ItemStore: Send + Sync {
+    async fn create_item_in_db(
+        &self,
+        request: CreateItemModel,
+        context: &AuditContextModel,
+    ) -> Result<ItemModel, ErrorModel>;
+
+    async fn delete_item_in_db(
+        &self,
+        item_id: i32,
+        context: &AuditContextModel,
+    ) -> Result<bool, NotFoundErrorModel>;
+
+    async fn update_item_in_db(
+        &self,
+        item_id: i32,
+        item_data: UpdateItemModel,
+        context: &AuditContextModel,
+    ) -> Result<ItemModel, NotFoundErrorModel>;
+
+    async fn get_item_by_id_from_db(&self, item_id: i32) -> Result<ItemModel, NotFoundErrorModel>;
+
+    /// Counts all items and those that ran out of stock.
+    async fn get_stock_summary_from_db(&self) -> Result<StockSummaryModel, ErrorModel>;
+}
+
+/// Reads of the audit log. Entries are written by the product and item
+/// stores together with the changes they record.
+#[async_trait::async_trait]
+pub trait AuditStore: Send + Sync {
+    /// Matching entries, newest first.
+    async fn get_audit_logs_from_db(
+        &self,
+        query: AuditQueryModel,
+    ) -> Result<Vec<AuditLogModel>, ErrorModel>;
+}
+
+/// Delivery bookkeeping of the outbox dispatcher. Events are queued by the
+/// product and item stores together with the changes they describe.
+#[async_trait::async_trait]
+pub trait OutboxStore: Send + Sync {
+    /// Undelivered events due for an attempt, oldest first. They are not due
+    /// again until `locked_until`, so concurrent dispatchers never get the
+    /// same event, and an event whose dispatcher died is retried after that.
+    async fn claim_pending_events_from_db(
+        &self,
+        limit: u64,
+        locked_until: NaiveDateTime,
+    ) -> Result<Vec<OutboxEventModel>, ErrorModel>;
+
+    async fn mark_event_sent_in_db(&self, event_id: i32) -> Result<(), ErrorModel>;
+
+    /// Records a failed delivery and when to try again.
+    async fn mark_event_failed_in_db(
+        &self,
+        event_id: i32,
+        attempts: i32,
+        error: String,
+        retry_at: NaiveDateTime,
+    ) -> Result<(), ErrorModel>;
+}
+
+/// Persistence operations the webhook service depends on.
+#[async_trait::async_trait]
+pub trait WebhookStore: Send + Sync {
+    async fn create_webhook_in_db(
+        &self,
+        url: String,
+        event_types: Vec<String>,
+        secret: String,
+    ) -> Result<WebhookModel, ErrorModel>;
+
+    async fn get_all_webhooks_from_db(&self) -> Result<Vec<WebhookModel>, ErrorModel>;
+
+    async fn get_webhook_by_id_from_db(
+        &self,
+        webhook_id: i32,
+    ) -> Result<WebhookModel, NotFoundErrorModel>;
+
+    async fn update_webhook_in_db(
+        &self,
+        webhook_id: i32,
+        webhook_data: UpdateWebhookModel,
+    ) -> Result<WebhookModel, NotFoundErrorModel>;
+
+    /// Deletes the subscription together with its deliveries.
+    async fn delete_webhook_in_db(&self, webhook_id: i32) -> Result<bool, NotFoundErrorModel>;
+
+    /// Queues the event for every active subscription to its type and returns
+    /// how many deliveries were queued. Subscriptions it was already queued
+    /// for are skipped.
+    async fn queue_webhook_deliveries_in_db(
+        &self,
+        event: &OutboxEventModel,
+    ) -> Result<usize, ErrorModel>;
+
+    /// Pending deliveries due for an attempt, oldest first. Their next
+    /// attempt moves to `locked_until`, so no other instance sends them
+    /// meanwhile, and they are retried after that if never updated.
+    async fn claim_due_webhook_deliveries_from_db(
+        &self,
+        limit: u64,
+        locked_until: NaiveDateTime,
+    ) -> Result<Vec<WebhookDeliveryModel>, ErrorModel>;
+
+    /// Stores the outcome of a delivery attempt.
+    async fn update_webhook_delivery_in_db(
+        &self,
+        delivery: WebhookDeliveryModel,
+    ) -> Result<(), ErrorModel>;
+
+    /// Deliveries of a subscription, newest first.
+    async fn get_webhook_deliveries_from_db(
+        &self,
+        webhook_id: i32,
+        limit: u64,
+    ) -> Result<Vec<WebhookDeliveryModel>, ErrorModel>;
+}
+
+/// Persistence operations the API key service depends on.
+#[async_trait::async_trait]
+pub trait ApiKeyStore: Send + Sync {
+    async fn create_api_key_in_db(
+        &self,
+        name: String,
+        prefix: String,
+        secret_hash: String,
+        scopes: Vec<String>,
+        expires_at: Option<NaiveDateTime>,
+    ) -> Result<ApiKeyModel, ErrorModel>;
+
+    async fn get_all_api_keys_from_db(&self) -> Result<Vec<ApiKeyModel>, ErrorModel>;
+
+    async fn get_api_key_by_prefix_from_db(
+        &self,
+        prefix: &str,
+    ) -> Result<ApiKeyModel, NotFoundErrorModel>;
+
+    /// Replaces the secret of a key, keeping its scopes and expiry.
+    async fn rotate_api_key_in_db(
+        &self,
+        api_key_id: i32,
+        prefix: String,
+        secret_hash: String,
+    ) -> Result<ApiKeyModel, NotFoundErrorModel>;
+
+    async fn revoke_api_key_in_db(&self, api_key_id: i32)
+        -> Result<ApiKeyModel, NotFoundErrorModel>;
+
+    async fn touch_api_key_in_db(&self, api_key_id: i32) -> Result<(), ErrorModel>;
+}
+
+/// Persistence operations the idempotency service depends on. Keys are
+/// scoped to the caller that sent them.
+#[async_trait::async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    async fn get_idempotency_key_from_db(
+        &self,
+        subject: &str,
+        key: &str,
+    ) -> Result<Option<IdempotencyRecordModel>, ErrorModel>;
+
+    /// Claims the key for a new request until `locked_until`; `false` when
+    /// it is already taken.
+    async fn create_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+        fingerprint: &str,
+        locked_until: NaiveDateTime,
+    ) -> Result<bool, ErrorModel>;
+
+    /// Claims an unanswered key whose claim ended before `now`; `false` when
+    /// another request holds or took it.
+    async fn take_over_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+        now: NaiveDateTime,
+        locked_until: NaiveDateTime,
+    ) -> Result<bool, ErrorModel>;
+
+    async fn complete_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+        status_code: u16,
+        response_body: String,
+    ) -> Result<(), ErrorModel>;
+
+    async fn delete_idempotency_key_in_db(&self, subject: &str, key: &str)
+        -> Result<(), ErrorModel>;
+
+    /// Deletes the key while it is unanswered and still claimed until
+    /// `locked_until`, leaving it to a request that took it over.
+    async fn release_idempotency_key_in_db(
+        &self,
+        subject: &str,
+        key: &str,
+        locked_until: NaiveDateTime,
+    ) -> Result<(), ErrorModel>;
+
+    /// Deletes every key created before `created_before`; returns how many.
+    async fn purge_idempotency_keys_in_db(
+        &self,
+        created_before: NaiveDateTime,
+    ) -> Result<u64, ErrorModel>;
+}
@@ -0,0 +1,99 @@
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, DbErr, ExecResult,
+    QueryResult, Statement, TransactionTrait,
+};
+
+use super::{
+    audit_repository::AuditRepository, item_repository::ItemRepository,
+    outbox_repository::OutboxRepository, product_repository::ProductRepository,
+};
+
+/// A single database transaction that several repositories can work against.
+///
+/// Nothing is persisted until `commit` is called; dropping the unit of work
+/// without committing rolls every change back.
+pub struct UnitOfWork {
+    txn: DatabaseTransaction,
+}
+
+impl UnitOfWork {
+    pub async fn begin(db: &DatabaseConnection) -> Result<Self, DbErr> {
+        Ok(Self {
+            txn: db.begin().await?,
+        })
+    }
+
+    pub fn product_repository(&self) -> ProductRepository<TransactionConnection<'_>> {
+        ProductRepository::new(TransactionConnection(&self.txn))
+    }
+
+    pub fn item_repository(&self) -> ItemRepository<TransactionConnection<'_>> {
+        ItemRepository::new(TransactionConnection(&self.txn))
+    }
+
+    pub fn audit_repository(&self) -> AuditRepository<TransactionConnection<'_>> {
+        AuditRepository::new(TransactionConnection(&self.txn))
+    }
+
+    pub fn outbox_repository(&self) -> OutboxRepository<TransactionConnection<'_>> {
+        OutboxRepository::new(TransactionConnection(&self.txn))
+    }
+
+    /// Commits when `result` is a success and rolls back otherwise; a failed
+    /// commit is reported through `database_error`.
+    pub async fn finish<T, E>(
+        self,
+        result: Result<T, E>,
+        database_error: impl FnOnce(String) -> E,
+    ) -> Result<T, E> {
+        match result {
+            Ok(value) => match self.commit().await {
+                Ok(_) => Ok(value),
+                Err(err) => Err(database_error(format!(
+                    "Failed to commit transaction: {}",
+                    err
+                ))),
+            },
+            Err(err) => {
+                let _ = self.rollback().await;
+                Err(err)
+            }
+        }
+    }
+
+    pub async fn commit(self) -> Result<(), DbErr> {
+        self.txn.commit().await
+    }
+
+    pub async fn rollback(self) -> Result<(), DbErr> {
+        self.txn.rollback().await
+    }
+}
+
+/// Borrowed handle to the transaction of a `UnitOfWork`, used as the
+/// connection of the repositories it hands out.
+#[derive(Clone, Copy)]
+pub struct TransactionConnection<'a>(&'a DatabaseTransaction);
+
+#[async_trait::async_trait]
+impl ConnectionTrait for TransactionConnection<'_> {
+    fn get_database_backend(&self) -> DbBackend {
+        self.0.get_database_backend()
+    }
+
+    async fn execute(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        self.0.execute(stmt).await
+    }
+
+    async fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        self.0.execute_unprepared(sql).await
+    }
+
+    async fn query_one(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        self.0.query_one(stmt).await
+    }
+
+    async fn query_all(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        self.0.query_all(stmt).await
+    }
+}
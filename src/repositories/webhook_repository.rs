@@ -0,0 +1,466 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{
+    sea_query::{Expr, LockBehavior, LockType},
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    ModelTrait, QueryFilter, QueryOrder, QuerySelect, Set, SqlErr, TransactionTrait,
+};
+use tracing::instrument;
+
+use crate::{
+    entities::{webhook_delivery, webhook_subscription},
+    metrics::time_query,
+    models::{
+        event_model::OutboxEventModel,
+        webhook_model::{
+            UpdateWebhookModel, WebhookDeliveryModel, WebhookDeliveryStatus, WebhookModel,
+        },
+        ErrorModel, NotFoundErrorModel,
+    },
+};
+
+use super::store::WebhookStore;
+
+#[derive(Clone)]
+pub struct WebhookRepository<C = DatabaseConnection> {
+    db: C,
+}
+
+impl<C: ConnectionTrait> WebhookRepository<C> {
+    pub fn new(db: C) -> Self {
+        Self { db }
+    }
+
+    #[instrument(skip(self, secret))]
+    pub async fn create_webhook_in_db(
+        &self,
+        url: String,
+        event_types: Vec<String>,
+        secret: String,
+    ) -> Result<WebhookModel, ErrorModel> {
+        let now = Utc::now().naive_utc();
+
+        let webhook_model = webhook_subscription::ActiveModel {
+            url: Set(url),
+            event_types: Set(event_types.join(" ")),
+            secret: Set(secret),
+            active: Set(true),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        };
+
+        match webhook_model.insert(&self.db).await {
+            Ok(inserted_webhook) => Ok(to_webhook_model(inserted_webhook)),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to create webhook: {}",
+                err
+            ))),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_all_webhooks_from_db(&self) -> Result<Vec<WebhookModel>, ErrorModel> {
+        match webhook_subscription::Entity::find()
+            .order_by_asc(webhook_subscription::Column::Id)
+            .all(&self.db)
+            .await
+        {
+            Ok(webhooks) => Ok(webhooks.into_iter().map(to_webhook_model).collect()),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to fetch webhooks: {}",
+                err
+            ))),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_webhook_by_id_from_db(
+        &self,
+        webhook_id: i32,
+    ) -> Result<WebhookModel, NotFoundErrorModel> {
+        match self.find_webhook(webhook_id).await {
+            Ok(webhook) => Ok(to_webhook_model(webhook)),
+            Err(err) => Err(err),
+        }
+    }
+
+    #[instrument(skip(self, webhook_data))]
+    pub async fn update_webhook_in_db(
+        &self,
+        webhook_id: i32,
+        webhook_data: UpdateWebhookModel,
+    ) -> Result<WebhookModel, NotFoundErrorModel> {
+        let mut updated_webhook: webhook_subscription::ActiveModel =
+            self.find_webhook(webhook_id).await?.into();
+
+        if let Some(url) = webhook_data.url {
+            updated_webhook.url = Set(url);
+        }
+        if let Some(event_types) = webhook_data.event_types {
+            updated_webhook.event_types = Set(event_types.join(" "));
+        }
+        if let Some(secret) = webhook_data.secret {
+            updated_webhook.secret = Set(secret);
+        }
+        if let Some(active) = webhook_data.active {
+            updated_webhook.active = Set(active);
+        }
+        updated_webhook.updated_at = Set(Utc::now().naive_utc());
+
+        match updated_webhook.update(&self.db).await {
+            Ok(updated_webhook) => Ok(to_webhook_model(updated_webhook)),
+            Err(err) => Err(NotFoundErrorModel::DatabaseError(format!(
+                "Failed to update webhook: {}",
+                err
+            ))),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn delete_webhook_in_db(&self, webhook_id: i32) -> Result<bool, NotFoundErrorModel> {
+        // Deliveries go with it through the cascading foreign key
+        match self.find_webhook(webhook_id).await?.delete(&self.db).await {
+            Ok(delete_result) => Ok(delete_result.rows_affected > 0),
+            Err(err) => Err(NotFoundErrorModel::DatabaseError(format!(
+                "Failed to delete webhook: {}",
+                err
+            ))),
+        }
+    }
+
+    #[instrument(skip(self, event), fields(event_id = event.id))]
+    pub async fn queue_webhook_deliveries_in_db(
+        &self,
+        event: &OutboxEventModel,
+    ) -> Result<usize, ErrorModel> {
+        let webhooks = match webhook_subscription::Entity::find()
+            .filter(webhook_subscription::Column::Active.eq(true))
+            .all(&self.db)
+            .await
+        {
+            Ok(webhooks) => webhooks,
+            Err(err) => {
+                return Err(ErrorModel::DatabaseError(format!(
+                    "Failed to fetch webhooks: {}",
+                    err
+                )))
+            }
+        };
+
+        let payload = match serde_json::to_value(event) {
+            Ok(payload) => payload,
+            Err(err) => {
+                return Err(ErrorModel::DatabaseError(format!(
+                    "Failed to serialize event: {}",
+                    err
+                )))
+            }
+        };
+        let now = Utc::now().naive_utc();
+
+        let mut queued = 0;
+        for webhook in webhooks.into_iter().filter(|webhook| {
+            webhook
+                .event_types
+                .split_whitespace()
+                .any(|event_type| event_type == event.event_type)
+        }) {
+            let delivery = webhook_delivery::ActiveModel {
+                subscription_id: Set(webhook.id),
+                event_id: Set(event.id),
+                event_type: Set(event.event_type.clone()),
+                payload: Set(payload.clone()),
+                status: Set(WebhookDeliveryStatus::Pending.as_str().to_string()),
+                attempts: Set(0),
+                next_attempt_at: Set(now),
+                created_at: Set(now),
+                ..Default::default()
+            };
+
+            match delivery.insert(&self.db).await {
+                Ok(_) => queued += 1,
+                // The outbox handed the event over before, e.g. after a crash
+                Err(err) if matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                    continue
+                }
+                Err(err) => {
+                    return Err(ErrorModel::DatabaseError(format!(
+                        "Failed to queue webhook delivery: {}",
+                        err
+                    )))
+                }
+            }
+        }
+
+        Ok(queued)
+    }
+
+    #[instrument(skip(self, delivery), fields(delivery_id = delivery.id))]
+    pub async fn update_webhook_delivery_in_db(
+        &self,
+        delivery: WebhookDeliveryModel,
+    ) -> Result<(), ErrorModel> {
+        let updated_delivery = webhook_delivery::ActiveModel {
+            id: Set(delivery.id),
+            status: Set(delivery.status),
+            attempts: Set(delivery.attempts),
+            response_status: Set(delivery.response_status),
+            last_error: Set(delivery.last_error),
+            next_attempt_at: Set(delivery.next_attempt_at),
+            delivered_at: Set(delivery.delivered_at),
+            ..Default::default()
+        };
+
+        match updated_delivery.update(&self.db).await {
+            Ok(_) => Ok(()),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to record webhook delivery: {}",
+                err
+            ))),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_webhook_deliveries_from_db(
+        &self,
+        webhook_id: i32,
+        limit: u64,
+    ) -> Result<Vec<WebhookDeliveryModel>, ErrorModel> {
+        match webhook_delivery::Entity::find()
+            .filter(webhook_delivery::Column::SubscriptionId.eq(webhook_id))
+            .order_by_desc(webhook_delivery::Column::Id)
+            .limit(limit)
+            .all(&self.db)
+            .await
+        {
+            Ok(deliveries) => Ok(deliveries
+                .into_iter()
+                .map(to_webhook_delivery_model)
+                .collect()),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to fetch webhook deliveries: {}",
+                err
+            ))),
+        }
+    }
+
+    async fn find_webhook(
+        &self,
+        webhook_id: i32,
+    ) -> Result<webhook_subscription::Model, NotFoundErrorModel> {
+        match webhook_subscription::Entity::find_by_id(webhook_id)
+            .one(&self.db)
+            .await
+        {
+            Ok(Some(webhook)) => Ok(webhook),
+            Ok(None) => Err(NotFoundErrorModel::NotFoundError(format!(
+                "Webhook with ID {} not found",
+                webhook_id
+            ))),
+            Err(err) => Err(NotFoundErrorModel::DatabaseError(format!(
+                "Failed to retrieve webhook: {}",
+                err
+            ))),
+        }
+    }
+}
+
+impl WebhookRepository {
+    #[instrument(skip(self))]
+    pub async fn claim_due_webhook_deliveries_from_db(
+        &self,
+        limit: u64,
+        locked_until: NaiveDateTime,
+    ) -> Result<Vec<WebhookDeliveryModel>, ErrorModel> {
+        match self.claim_due_deliveries(limit, locked_until).await {
+            Ok(deliveries) => Ok(deliveries
+                .into_iter()
+                .map(to_webhook_delivery_model)
+                .collect()),
+            Err(err) => Err(ErrorModel::DatabaseError(format!(
+                "Failed to claim due webhook deliveries: {}",
+                err
+            ))),
+        }
+    }
+
+    /// Reads the due deliveries and pushes their next attempt to
+    /// `locked_until` in one transaction, skipping rows another transaction
+    /// is claiming on Postgres.
+    async fn claim_due_deliveries(
+        &self,
+        limit: u64,
+        locked_until: NaiveDateTime,
+    ) -> Result<Vec<webhook_delivery::Model>, DbErr> {
+        let txn = self.db.begin().await?;
+
+        let deliveries = webhook_delivery::Entity::find()
+            .filter(webhook_delivery::Column::Status.eq(WebhookDeliveryStatus::Pending.as_str()))
+            .filter(webhook_delivery::Column::NextAttemptAt.lte(Utc::now().naive_utc()))
+            .order_by_asc(webhook_delivery::Column::Id)
+            .limit(limit)
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+            .all(&txn)
+            .await?;
+
+        if !deliveries.is_empty() {
+            webhook_delivery::Entity::update_many()
+                .col_expr(
+                    webhook_delivery::Column::NextAttemptAt,
+                    Expr::value(locked_until),
+                )
+                .filter(
+                    webhook_delivery::Column::Id
+                        .is_in(deliveries.iter().map(|delivery| delivery.id)),
+                )
+                .exec(&txn)
+                .await?;
+        }
+
+        txn.commit().await?;
+        Ok(deliveries)
+    }
+}
+
+pub(crate) fn to_webhook_model(webhook: webhook_subscription::Model) -> WebhookModel {
+    WebhookModel {
+        id: webhook.id,
+        url: webhook.url,
+        event_types: webhook
+            .event_types
+            .split_whitespace()
+            .map(str::to_string)
+            .collect(),
+        active: webhook.active,
+        created_at: webhook.created_at,
+        updated_at: webhook.updated_at,
+        secret: webhook.secret,
+    }
+}
+
+pub(crate) fn to_webhook_delivery_model(delivery: webhook_delivery::Model) -> WebhookDeliveryModel {
+    WebhookDeliveryModel {
+        id: delivery.id,
+        subscription_id: delivery.subscription_id,
+        event_id: delivery.event_id,
+        event_type: delivery.event_type,
+        payload: delivery.payload,
+        status: delivery.status,
+        attempts: delivery.attempts,
+        response_status: delivery.response_status,
+        last_error: delivery.last_error,
+        next_attempt_at: delivery.next_attempt_at,
+        created_at: delivery.created_at,
+        delivered_at: delivery.delivered_at,
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookStore for WebhookRepository {
+    async fn create_webhook_in_db(
+        &self,
+        url: String,
+        event_types: Vec<String>,
+        secret: String,
+    ) -> Result<WebhookModel, ErrorModel> {
+        time_query(
+            "webhook",
+            "create_webhook_in_db",
+            WebhookRepository::create_webhook_in_db(self, url, event_types, secret),
+        )
+        .await
+    }
+
+    async fn get_all_webhooks_from_db(&self) -> Result<Vec<WebhookModel>, ErrorModel> {
+        time_query(
+            "webhook",
+            "get_all_webhooks_from_db",
+            WebhookRepository::get_all_webhooks_from_db(self),
+        )
+        .await
+    }
+
+    async fn get_webhook_by_id_from_db(
+        &self,
+        webhook_id: i32,
+    ) -> Result<WebhookModel, NotFoundErrorModel> {
+        time_query(
+            "webhook",
+            "get_webhook_by_id_from_db",
+            WebhookRepository::get_webhook_by_id_from_db(self, webhook_id),
+        )
+        .await
+    }
+
+    async fn update_webhook_in_db(
+        &self,
+        webhook_id: i32,
+        webhook_data: UpdateWebhookModel,
+    ) -> Result<WebhookModel, NotFoundErrorModel> {
+        time_query(
+            "webhook",
+            "update_webhook_in_db",
+            WebhookRepository::update_webhook_in_db(self, webhook_id, webhook_data),
+        )
+        .await
+    }
+
+    async fn delete_webhook_in_db(&self, webhook_id: i32) -> Result<bool, NotFoundErrorModel> {
+        time_query(
+            "webhook",
+            "delete_webhook_in_db",
+            WebhookRepository::delete_webhook_in_db(self, webhook_id),
+        )
+        .await
+    }
+
+    async fn queue_webhook_deliveries_in_db(
+        &self,
+        event: &OutboxEventModel,
+    ) -> Result<usize, ErrorModel> {
+        time_query(
+            "webhook",
+            "queue_webhook_deliveries_in_db",
+            WebhookRepository::queue_webhook_deliveries_in_db(self, event),
+        )
+        .await
+    }
+
+    async fn claim_due_webhook_deliveries_from_db(
+        &self,
+        limit: u64,
+        locked_until: NaiveDateTime,
+    ) -> Result<Vec<WebhookDeliveryModel>, ErrorModel> {
+        time_query(
+            "webhook",
+            "claim_due_webhook_deliveries_from_db",
+            WebhookRepository::claim_due_webhook_deliveries_from_db(self, limit, locked_until),
+        )
+        .await
+    }
+
+    async fn update_webhook_delivery_in_db(
+        &self,
+        delivery: WebhookDeliveryModel,
+    ) -> Result<(), ErrorModel> {
+        time_query(
+            "webhook",
+            "update_webhook_delivery_in_db",
+            WebhookRepository::update_webhook_delivery_in_db(self, delivery),
+        )
+        .await
+    }
+
+    async fn get_webhook_deliveries_from_db(
+        &self,
+        webhook_id: i32,
+        limit: u64,
+    ) -> Result<Vec<WebhookDeliveryModel>, ErrorModel> {
+        time_query(
+            "webhook",
+            "get_webhook_deliveries_from_db",
+            WebhookRepository::get_webhook_deliveries_from_db(self, webhook_id, limit),
+        )
+        .await
+    }
+}
@@ -0,0 +1,25 @@
+use axum::{
+    middleware::from_fn_with_state,
+    routing::{delete, get, post},
+    Router,
+};
+
+use crate::{
+    handler::api_key_handler::{create_api_key, get_all_api_keys, revoke_api_key, rotate_api_key},
+    middleware::{
+        auth::require_permission,
+        rate_limit::{rate_limit, RouteGroup},
+    },
+    models::auth_model::Permission,
+    state::AppState,
+};
+
+pub fn api_key_routes() -> Router<AppState> {
+    Router::new()
+        .route("/api-keys", get(get_all_api_keys))
+        .route("/api-keys", post(create_api_key))
+        .route("/api-keys/:id", delete(revoke_api_key))
+        .route("/api-keys/:id/rotate", post(rotate_api_key))
+        .route_layer(from_fn_with_state(Permission::ApiKeysManage, require_permission))
+        .route_layer(from_fn_with_state(RouteGroup::Write, rate_limit))
+}
@@ -0,0 +1,21 @@
+use axum::{middleware::from_fn_with_state, routing::get, Router};
+
+use crate::{
+    handler::audit_handler::get_audit_logs,
+    middleware::{
+        auth::require_permission,
+        rate_limit::{rate_limit, RouteGroup},
+    },
+    models::auth_model::Permission,
+    state::AppState,
+};
+
+pub fn audit_routes() -> Router<AppState> {
+    Router::new()
+        .route("/audit", get(get_audit_logs))
+        .route_layer(from_fn_with_state(
+            Permission::AuditRead,
+            require_permission,
+        ))
+        .route_layer(from_fn_with_state(RouteGroup::Read, rate_limit))
+}
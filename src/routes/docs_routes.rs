@@ -0,0 +1,11 @@
+use axum::Router;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::{openapi::ApiDoc, state::AppState};
+
+pub fn docs_routes() -> Router<AppState> {
+    SwaggerUi::new("/docs")
+        .url("/openapi.json", ApiDoc::openapi())
+        .into()
+}
@@ -0,0 +1,21 @@
+use axum::{middleware::from_fn_with_state, routing::get, Router};
+
+use crate::{
+    handler::event_handler::stream_events,
+    middleware::{
+        auth::require_permission,
+        rate_limit::{rate_limit, RouteGroup},
+    },
+    models::auth_model::Permission,
+    state::AppState,
+};
+
+pub fn event_routes() -> Router<AppState> {
+    Router::new()
+        .route("/events/stream", get(stream_events))
+        .route_layer(from_fn_with_state(
+            Permission::InventoryRead,
+            require_permission,
+        ))
+        .route_layer(from_fn_with_state(RouteGroup::Read, rate_limit))
+}
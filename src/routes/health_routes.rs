@@ -0,0 +1,12 @@
+use axum::{routing::get, Router};
+
+use crate::{handler::{health_handler::{liveness, readiness, version}, metrics_handler::get_metrics}, state::AppState};
+
+
+pub fn health_routes() -> Router<AppState> {
+    Router::new()
+    .route("/healthz", get(liveness))
+    .route("/readyz", get(readiness))
+    .route("/version", get(version))
+    .route("/metrics", get(get_metrics))
+}
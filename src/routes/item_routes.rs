@@ -1,24 +1,23 @@
 use axum::{
+    middleware::{from_fn, from_fn_with_state},
     routing::{get, post, put, delete}, 
     Router,
-    http::Method
 };
-use tower_http::cors::{Any, CorsLayer};
 
-use crate::{handler::item_handler::{create_item, delete_item,update_item,get_item_by_id}, services::item_service::ItemService};
+use crate::{handler::item_handler::{create_item, delete_item,update_item,get_item_by_id}, middleware::{auth::require_permission, idempotency::idempotency, rate_limit::{rate_limit, RouteGroup}}, models::auth_model::Permission, state::AppState};
 
 
-pub fn item_routes(item_service:ItemService) -> Router {
-    let cors = CorsLayer::new()
-    .allow_methods([Method::GET, Method::POST, Method::DELETE, Method::PUT])
-    .allow_origin(Any);
-
-    Router::new()
+pub fn item_routes() -> Router<AppState> {
+    let reads = Router::new()
     .route("/item/:id", get(get_item_by_id))
-    .route("/item", post(create_item))
-    .route("/item/:id", put(update_item))
-    .route("/item/:id", delete(delete_item))
-    .layer(cors)
-    .with_state(item_service)
+    .route_layer(from_fn_with_state(RouteGroup::Read, rate_limit));
+
+    let writes = Router::new()
+    .route("/item", post(create_item).route_layer(from_fn(idempotency)).route_layer(from_fn_with_state(Permission::InventoryWrite, require_permission)))
+    .route("/item/:id", put(update_item).route_layer(from_fn(idempotency)).route_layer(from_fn_with_state(Permission::InventoryWrite, require_permission)))
+    .route("/item/:id", delete(delete_item).route_layer(from_fn_with_state(Permission::CatalogDelete, require_permission)))
+    .route_layer(from_fn_with_state(RouteGroup::Write, rate_limit));
+
+    reads.merge(writes)
 
 }
@@ -1,2 +1,8 @@
+pub mod api_key_routes;
+pub mod audit_routes;
+pub mod docs_routes;
+pub mod event_routes;
+pub mod health_routes;
 pub mod product_routes;
-pub mod item_routes;
\ No newline at end of file
+pub mod item_routes;
+pub mod webhook_routes;
@@ -1,25 +1,24 @@
 use axum::{
+    middleware::{from_fn, from_fn_with_state},
     routing::{get, post, put, delete}, 
     Router,
-    http::Method
 };
-use tower_http::cors::{Any, CorsLayer};
 
-use crate::{handler::product_handler::{create_product, delete_product, get_all_products, update_product}, services::product_service::ProductService};
+use crate::{handler::product_handler::{create_product, delete_product, get_all_products, update_product}, middleware::{auth::require_permission, idempotency::idempotency, rate_limit::{limit_concurrency, rate_limit, RouteGroup}}, models::auth_model::Permission, state::AppState};
 
 
-pub fn product_routes(product_service:ProductService) -> Router {
-    let cors = CorsLayer::new()
-    .allow_methods([Method::GET, Method::POST, Method::DELETE, Method::PUT])
-    .allow_origin(Any);
+pub fn product_routes() -> Router<AppState> {
+    // Listing loads the whole catalog and creating may insert many items
+    let reads = Router::new()
+    .route("/product", get(get_all_products).route_layer(from_fn(limit_concurrency)))
+    .route_layer(from_fn_with_state(RouteGroup::Read, rate_limit));
 
-    Router::new()
-    .route("/product", post(create_product))
-    .route("/product", get(get_all_products))
-    .route("/product/:id", put(update_product))
-    .route("/product/:id", delete(delete_product))
-    .layer(cors)
-    .with_state(product_service)
+    let writes = Router::new()
+    .route("/product", post(create_product).route_layer(from_fn(idempotency)).route_layer(from_fn_with_state(Permission::CatalogWrite, require_permission)).route_layer(from_fn(limit_concurrency)))
+    .route("/product/:id", put(update_product).route_layer(from_fn_with_state(Permission::CatalogWrite, require_permission)))
+    .route("/product/:id", delete(delete_product).route_layer(from_fn_with_state(Permission::CatalogDelete, require_permission)))
+    .route_layer(from_fn_with_state(RouteGroup::Write, rate_limit));
 
-}
+    reads.merge(writes)
 
+}
@@ -0,0 +1,41 @@
+use axum::{
+    middleware::from_fn_with_state,
+    routing::{get, post, put},
+    Router,
+};
+
+use crate::{
+    handler::webhook_handler::{
+        create_webhook, delete_webhook, get_all_webhooks, get_webhook_by_id,
+        get_webhook_deliveries, update_webhook,
+    },
+    middleware::{
+        auth::require_permission,
+        rate_limit::{rate_limit, RouteGroup},
+    },
+    models::auth_model::Permission,
+    state::AppState,
+};
+
+pub fn webhook_routes() -> Router<AppState> {
+    let reads = Router::new()
+        .route("/webhooks", get(get_all_webhooks))
+        .route("/webhooks/:id", get(get_webhook_by_id))
+        .route("/webhooks/:id/deliveries", get(get_webhook_deliveries))
+        .route_layer(from_fn_with_state(
+            Permission::WebhooksManage,
+            require_permission,
+        ))
+        .route_layer(from_fn_with_state(RouteGroup::Read, rate_limit));
+
+    let writes = Router::new()
+        .route("/webhooks", post(create_webhook))
+        .route("/webhooks/:id", put(update_webhook).delete(delete_webhook))
+        .route_layer(from_fn_with_state(
+            Permission::WebhooksManage,
+            require_permission,
+        ))
+        .route_layer(from_fn_with_state(RouteGroup::Write, rate_limit));
+
+    reads.merge(writes)
+}
@@ -0,0 +1,220 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use chrono::Utc;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::{
+    models::{
+        api_key_model::{ApiKeyModel, CreateApiKeyModel, IssuedApiKeyModel},
+        auth_model::{CallerModel, Permission},
+        AuthErrorModel, ErrorModel, NotFoundErrorModel,
+    },
+    repositories::store::ApiKeyStore,
+};
+
+/// Marks the plaintext keys handed out, `pk_<prefix>_<secret>`.
+const KEY_MARKER: &str = "pk";
+
+#[derive(Clone)]
+pub struct ApiKeyService {
+    api_key_repository: Arc<dyn ApiKeyStore>,
+}
+
+impl ApiKeyService {
+    pub fn new(api_key_repository: Arc<dyn ApiKeyStore>) -> Self {
+        Self { api_key_repository }
+    }
+
+    pub async fn create_api_key(
+        &self,
+        request: CreateApiKeyModel,
+    ) -> Result<IssuedApiKeyModel, ErrorModel> {
+        if request.name.trim().is_empty() {
+            return Err(ErrorModel::ValidationError("Name is required".to_string()));
+        } else if request.scopes.is_empty() {
+            return Err(ErrorModel::ValidationError("Scopes are required".to_string()));
+        } else if let Some(scope) = request
+            .scopes
+            .iter()
+            .find(|scope| scope.parse::<Permission>().is_err())
+        {
+            return Err(ErrorModel::ValidationError(format!("Unknown scope {}", scope)));
+        } else if request
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now().naive_utc())
+        {
+            return Err(ErrorModel::ValidationError(
+                "Expiry must be in the future".to_string(),
+            ));
+        }
+
+        let secret = Secret::generate();
+        let api_key = self
+            .api_key_repository
+            .create_api_key_in_db(
+                request.name,
+                secret.prefix.clone(),
+                secret.hash(),
+                request.scopes,
+                request.expires_at,
+            )
+            .await?;
+
+        Ok(secret.issue(api_key))
+    }
+
+    pub async fn get_all_api_keys(&self) -> Result<Vec<ApiKeyModel>, ErrorModel> {
+        self.api_key_repository.get_all_api_keys_from_db().await
+    }
+
+    /// Issues a new secret for the key; the previous one stops working.
+    pub async fn rotate_api_key(
+        &self,
+        api_key_id: i32,
+    ) -> Result<IssuedApiKeyModel, NotFoundErrorModel> {
+        let secret = Secret::generate();
+        let api_key = self
+            .api_key_repository
+            .rotate_api_key_in_db(api_key_id, secret.prefix.clone(), secret.hash())
+            .await?;
+
+        Ok(secret.issue(api_key))
+    }
+
+    pub async fn revoke_api_key(&self, api_key_id: i32) -> Result<ApiKeyModel, NotFoundErrorModel> {
+        self.api_key_repository.revoke_api_key_in_db(api_key_id).await
+    }
+
+    /// Resolves the caller behind an `X-Api-Key` value and records the use.
+    pub async fn authenticate(&self, key: &str) -> Result<CallerModel, AuthErrorModel> {
+        let invalid = || AuthErrorModel::Unauthorized("Invalid API key".to_string());
+
+        let (prefix, secret) = key
+            .strip_prefix(KEY_MARKER)
+            .and_then(|rest| rest.strip_prefix('_'))
+            .and_then(|rest| rest.split_once('_'))
+            .ok_or_else(invalid)?;
+
+        let api_key = match self.api_key_repository.get_api_key_by_prefix_from_db(prefix).await {
+            Ok(api_key) => api_key,
+            Err(NotFoundErrorModel::DatabaseError(msg)) => {
+                warn!("Failed to look up API key: {}", msg);
+                return Err(invalid());
+            }
+            Err(_) => return Err(invalid()),
+        };
+
+        let expired = api_key
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now().naive_utc());
+        if hash(secret) != api_key.secret_hash || api_key.revoked_at.is_some() || expired {
+            return Err(invalid());
+        }
+
+        if let Err(ErrorModel::DatabaseError(msg) | ErrorModel::ValidationError(msg)) =
+            self.api_key_repository.touch_api_key_in_db(api_key.id).await
+        {
+            warn!("Failed to record API key use: {}", msg);
+        }
+
+        Ok(CallerModel {
+            subject: format!("api-key:{}", api_key.id),
+            permissions: api_key
+                .scopes
+                .iter()
+                .filter_map(|scope| scope.parse().ok())
+                .collect::<BTreeSet<_>>(),
+        })
+    }
+}
+
+/// Freshly generated key material.
+struct Secret {
+    prefix: String,
+    secret: String,
+}
+
+impl Secret {
+    fn generate() -> Self {
+        Self {
+            prefix: random_hex::<6>(),
+            secret: random_hex::<32>(),
+        }
+    }
+
+    fn hash(&self) -> String {
+        hash(&self.secret)
+    }
+
+    fn issue(self, api_key: ApiKeyModel) -> IssuedApiKeyModel {
+        IssuedApiKeyModel {
+            key: format!("{}_{}_{}", KEY_MARKER, self.prefix, self.secret),
+            api_key,
+        }
+    }
+}
+
+fn random_hex<const N: usize>() -> String {
+    let mut bytes = [0u8; N];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+// Secrets are 256 random bits, so a plain digest is enough to protect them
+fn hash(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::in_memory::InMemoryStore;
+
+    fn service() -> ApiKeyService {
+        ApiKeyService::new(Arc::new(InMemoryStore::new()))
+    }
+
+    fn request(scopes: &[&str]) -> CreateApiKeyModel {
+        CreateApiKeyModel {
+            name: "partner".to_string(),
+            scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+            expires_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn issued_key_authenticates_with_its_scopes() {
+        let service = service();
+        let issued = service.create_api_key(request(&["inventory:write"])).await.unwrap();
+
+        let caller = service.authenticate(&issued.key).await.unwrap();
+
+        assert_eq!(caller.subject, format!("api-key:{}", issued.api_key.id));
+        assert!(caller.has(Permission::InventoryWrite));
+        assert!(!caller.has(Permission::CatalogWrite));
+        let keys = service.get_all_api_keys().await.unwrap();
+        assert!(keys[0].last_used_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn rotated_and_revoked_keys_stop_working() {
+        let service = service();
+        let issued = service.create_api_key(request(&["catalog:write"])).await.unwrap();
+
+        let rotated = service.rotate_api_key(issued.api_key.id).await.unwrap();
+        assert!(service.authenticate(&issued.key).await.is_err());
+        assert!(service.authenticate(&rotated.key).await.is_ok());
+
+        service.revoke_api_key(issued.api_key.id).await.unwrap();
+        assert!(service.authenticate(&rotated.key).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn unknown_scopes_are_rejected() {
+        let result = service().create_api_key(request(&["catalog:everything"])).await;
+
+        assert!(matches!(result, Err(ErrorModel::ValidationError(_))));
+    }
+}
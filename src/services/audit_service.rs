@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use crate::{
+    models::{
+        audit_model::{AuditEntity, AuditLogModel, AuditQueryModel},
+        ErrorModel,
+    },
+    repositories::store::AuditStore,
+};
+
+/// Most entries one query may return.
+const MAX_AUDIT_LIMIT: u64 = 1000;
+
+#[derive(Clone)]
+pub struct AuditService {
+    audit_repository: Arc<dyn AuditStore>,
+}
+
+impl AuditService {
+    pub fn new(audit_repository: Arc<dyn AuditStore>) -> Self {
+        Self { audit_repository }
+    }
+
+    pub async fn get_audit_logs(
+        &self,
+        query: AuditQueryModel,
+    ) -> Result<Vec<AuditLogModel>, ErrorModel> {
+        let known_entity = |entity: &str| {
+            [AuditEntity::Product, AuditEntity::Item]
+                .iter()
+                .any(|known| known.as_str() == entity)
+        };
+
+        if let Some(entity) = query
+            .entity
+            .as_deref()
+            .filter(|entity| !known_entity(entity))
+        {
+            return Err(ErrorModel::ValidationError(format!(
+                "Unknown entity {}",
+                entity
+            )));
+        } else if query.id.is_some() && query.entity.is_none() {
+            return Err(ErrorModel::ValidationError(
+                "Filtering by id needs an entity".to_string(),
+            ));
+        } else if matches!((query.from, query.to), (Some(from), Some(to)) if from >= to) {
+            return Err(ErrorModel::ValidationError(
+                "from must be before to".to_string(),
+            ));
+        } else if query
+            .limit
+            .is_some_and(|limit| limit == 0 || limit > MAX_AUDIT_LIMIT)
+        {
+            return Err(ErrorModel::ValidationError(format!(
+                "limit must be between 1 and {}",
+                MAX_AUDIT_LIMIT
+            )));
+        }
+
+        self.audit_repository.get_audit_logs_from_db(query).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::{
+            audit_model::AuditContextModel,
+            item_model::{CreateProductItemModel, UpdateItemModel},
+            product_model::CreateProductModal,
+        },
+        repositories::{
+            in_memory::InMemoryStore,
+            store::{ItemStore, ProductStore},
+        },
+    };
+
+    #[tokio::test]
+    async fn changes_are_recorded_with_snapshots() {
+        let store = InMemoryStore::new();
+        let service = AuditService::new(Arc::new(store.clone()));
+        let context = AuditContextModel::new("alice", Some("req-1".to_string()));
+        let product = store
+            .create_product_in_db(
+                CreateProductModal {
+                    name: "T-shirt".to_string(),
+                    description: None,
+                    items: vec![CreateProductItemModel {
+                        color: "red".to_string(),
+                        stock: 3,
+                        size: "M".to_string(),
+                    }],
+                },
+                &context,
+            )
+            .await
+            .unwrap();
+        store
+            .update_item_in_db(
+                product.items[0].id,
+                UpdateItemModel {
+                    size: None,
+                    color: None,
+                    stock: Some(2),
+                },
+                &context,
+            )
+            .await
+            .unwrap();
+
+        let entries = service
+            .get_audit_logs(AuditQueryModel {
+                entity: Some("item".to_string()),
+                id: Some(product.items[0].id),
+                ..AuditQueryModel::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, "update");
+        assert_eq!(entries[1].action, "create");
+        assert_eq!(entries[0].before.as_ref().unwrap()["stock"], 3);
+        assert_eq!(entries[0].after.as_ref().unwrap()["stock"], 2);
+        assert_eq!(entries[0].actor, "alice");
+        assert_eq!(entries[0].request_id.as_deref(), Some("req-1"));
+    }
+
+    #[tokio::test]
+    async fn id_filter_needs_an_entity() {
+        let service = AuditService::new(Arc::new(InMemoryStore::new()));
+
+        let result = service
+            .get_audit_logs(AuditQueryModel {
+                id: Some(1),
+                ..AuditQueryModel::default()
+            })
+            .await;
+
+        assert!(matches!(result, Err(ErrorModel::ValidationError(_))));
+    }
+}
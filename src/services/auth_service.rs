@@ -0,0 +1,198 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+
+use super::api_key_service::ApiKeyService;
+use crate::{
+    config::AuthConfig,
+    models::{
+        auth_model::{role_permissions, CallerModel, Permission},
+        AuthErrorModel,
+    },
+};
+
+/// Claims the service reads from a bearer token. Permissions are granted
+/// directly in `permissions` or `scope`, or through `roles`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    /// Space-separated OAuth scopes.
+    #[serde(default)]
+    pub scope: Option<String>,
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+impl Claims {
+    fn into_caller(self) -> CallerModel {
+        let scopes = self.scope.iter().flat_map(|scope| scope.split_whitespace());
+        let mut permissions: BTreeSet<Permission> = self
+            .permissions
+            .iter()
+            .map(String::as_str)
+            .chain(scopes)
+            .filter_map(|name| name.parse().ok())
+            .collect();
+        for role in &self.roles {
+            permissions.extend(role_permissions(role));
+        }
+
+        CallerModel {
+            subject: self.sub,
+            permissions,
+        }
+    }
+}
+
+/// Validates bearer tokens against the configured HS256 secret and RS256 JWKS,
+/// and API keys against the stored ones.
+#[derive(Clone)]
+pub struct AuthService {
+    keys: Arc<Keys>,
+    api_key_service: ApiKeyService,
+}
+
+struct Keys {
+    secret: Option<DecodingKey>,
+    jwks: JwkSet,
+    issuer: Option<String>,
+    audience: Option<String>,
+}
+
+impl AuthService {
+    pub fn new(config: &AuthConfig, api_key_service: ApiKeyService) -> Self {
+        Self {
+            keys: Arc::new(Keys {
+                secret: config
+                    .jwt_secret
+                    .as_ref()
+                    .map(|secret| DecodingKey::from_secret(secret.as_bytes())),
+                jwks: config.jwks.clone().unwrap_or(JwkSet { keys: Vec::new() }),
+                issuer: config.issuer.clone(),
+                audience: config.audience.clone(),
+            }),
+            api_key_service,
+        }
+    }
+
+    pub async fn authenticate_api_key(&self, key: &str) -> Result<CallerModel, AuthErrorModel> {
+        self.api_key_service.authenticate(key).await
+    }
+
+    pub fn authenticate(&self, token: &str) -> Result<CallerModel, AuthErrorModel> {
+        let header = decode_header(token)
+            .map_err(|_| AuthErrorModel::Unauthorized("Malformed bearer token".to_string()))?;
+
+        let key = match header.alg {
+            Algorithm::HS256 => self.keys.secret.clone(),
+            Algorithm::RS256 => {
+                let jwk = match &header.kid {
+                    Some(kid) => self.keys.jwks.find(kid),
+                    None => self.keys.jwks.keys.first(),
+                };
+                jwk.and_then(|jwk| DecodingKey::from_jwk(jwk).ok())
+            }
+            _ => None,
+        }
+        .ok_or_else(|| {
+            AuthErrorModel::Unauthorized(format!("No key accepts {:?} tokens", header.alg))
+        })?;
+
+        let mut validation = Validation::new(header.alg);
+        if let Some(issuer) = &self.keys.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        match &self.keys.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        decode::<Claims>(token, &key, &validation)
+            .map(|data| data.claims.into_caller())
+            .map_err(|err| AuthErrorModel::Unauthorized(format!("Invalid bearer token: {}", err)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+
+    use super::*;
+    use crate::repositories::in_memory::InMemoryStore;
+
+    fn service() -> AuthService {
+        AuthService::new(
+            &AuthConfig {
+                jwt_secret: Some("secret".to_string()),
+                issuer: Some("https://issuer.example.com".to_string()),
+                ..AuthConfig::default()
+            },
+            ApiKeyService::new(Arc::new(InMemoryStore::new())),
+        )
+    }
+
+    fn token(claims: serde_json::Value, secret: &str) -> String {
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    fn expires() -> i64 {
+        chrono::Utc::now().timestamp() + 60
+    }
+
+    #[test]
+    fn accepts_token_signed_with_secret() {
+        let token = token(
+            json!({"sub": "alice", "iss": "https://issuer.example.com", "exp": expires()}),
+            "secret",
+        );
+
+        assert_eq!(service().authenticate(&token).unwrap().subject, "alice");
+    }
+
+    #[test]
+    fn permissions_come_from_claims_scopes_and_roles() {
+        let token = token(
+            json!({
+                "sub": "bob",
+                "iss": "https://issuer.example.com",
+                "exp": expires(),
+                "scope": "openid catalog:delete",
+                "roles": ["warehouse", "unknown"],
+            }),
+            "secret",
+        );
+
+        let caller = service().authenticate(&token).unwrap();
+
+        assert!(caller.has(Permission::CatalogDelete));
+        assert!(caller.has(Permission::InventoryWrite));
+        assert!(caller.has(Permission::InventoryRead));
+        assert!(!caller.has(Permission::CatalogWrite));
+    }
+
+    #[test]
+    fn rejects_wrong_secret_issuer_and_expired_tokens() {
+        let tokens = [
+            json!({"sub": "alice", "iss": "https://issuer.example.com", "exp": expires()}),
+            json!({"sub": "alice", "iss": "https://other.example.com", "exp": expires()}),
+            json!({"sub": "alice", "iss": "https://issuer.example.com", "exp": 1}),
+        ];
+        let secrets = ["wrong", "secret", "secret"];
+
+        for (claims, secret) in tokens.into_iter().zip(secrets) {
+            assert!(matches!(
+                service().authenticate(&token(claims, secret)),
+                Err(AuthErrorModel::Unauthorized(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::sync::{broadcast, watch};
+
+use crate::{
+    config::EventStreamConfig,
+    models::event_model::{InventoryChangeModel, LiveEventModel},
+};
+
+/// Fans committed inventory changes out to `GET /events/stream` clients and
+/// keeps the latest ones for clients resuming with `Last-Event-ID`.
+///
+/// Only changes made through this instance are seen, and event IDs start
+/// over when it restarts.
+#[derive(Clone)]
+pub struct EventStreamService {
+    sender: broadcast::Sender<LiveEventModel>,
+    history: Arc<Mutex<History>>,
+    keep_alive: Duration,
+    /// Set once the server shuts down; open streams end so it can drain.
+    closed: Arc<watch::Sender<bool>>,
+}
+
+struct History {
+    last_id: u64,
+    capacity: usize,
+    events: VecDeque<LiveEventModel>,
+}
+
+impl EventStreamService {
+    pub fn new(config: &EventStreamConfig) -> Self {
+        let (sender, _) = broadcast::channel(config.history);
+        Self {
+            sender,
+            history: Arc::new(Mutex::new(History {
+                last_id: 0,
+                capacity: config.history,
+                events: VecDeque::with_capacity(config.history),
+            })),
+            keep_alive: config.keep_alive,
+            closed: Arc::new(watch::Sender::new(false)),
+        }
+    }
+
+    /// Ends every open stream, and every stream opened from now on.
+    pub fn close(&self) {
+        self.closed.send_replace(true);
+    }
+
+    /// Resolves once `close` was called.
+    pub fn closed(&self) -> impl std::future::Future<Output = ()> + Send + 'static {
+        let mut closed = self.closed.subscribe();
+        async move {
+            // Fails only once the service is gone, which ends the streams too
+            let _ = closed.wait_for(|closed| *closed).await;
+        }
+    }
+
+    /// Time between two keep-alive comments on an idle stream.
+    pub fn keep_alive(&self) -> Duration {
+        self.keep_alive
+    }
+
+    /// Numbers the changes and sends them to every connected client.
+    pub fn publish(&self, changes: Vec<InventoryChangeModel>) {
+        let mut history = self.lock();
+        for change in changes {
+            history.last_id += 1;
+            let event = LiveEventModel {
+                id: history.last_id,
+                change,
+            };
+            if history.events.len() == history.capacity {
+                history.events.pop_front();
+            }
+            history.events.push_back(event.clone());
+            // Fails only when nobody is listening
+            let _ = self.sender.send(event);
+        }
+    }
+
+    /// The kept events published after `last_event_id`, followed on the
+    /// receiver by every later one, without gaps or repeats.
+    ///
+    /// An ID this instance has not handed out yet, e.g. one from before a
+    /// restart, replays every kept event.
+    pub fn subscribe(
+        &self,
+        last_event_id: Option<u64>,
+    ) -> (Vec<LiveEventModel>, broadcast::Receiver<LiveEventModel>) {
+        let history = self.lock();
+        let missed = match last_event_id {
+            Some(id) if id <= history.last_id => history
+                .events
+                .iter()
+                .filter(|event| event.id > id)
+                .cloned()
+                .collect(),
+            Some(_) => history.events.iter().cloned().collect(),
+            None => Vec::new(),
+        };
+
+        (missed, self.sender.subscribe())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, History> {
+        self.history
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::event_model::StockLevelModel;
+
+    fn service(history: usize) -> EventStreamService {
+        EventStreamService::new(&EventStreamConfig {
+            history,
+            keep_alive: Duration::from_secs(15),
+        })
+    }
+
+    fn stock(item_id: i32, stock: i32) -> InventoryChangeModel {
+        InventoryChangeModel::ItemStockChanged(StockLevelModel {
+            item_id,
+            product_id: 1,
+            stock,
+        })
+    }
+
+    fn ids(events: &[LiveEventModel]) -> Vec<u64> {
+        events.iter().map(|event| event.id).collect()
+    }
+
+    #[tokio::test]
+    async fn subscribers_get_later_events() {
+        let service = service(10);
+        service.publish(vec![stock(1, 5)]);
+
+        let (missed, mut receiver) = service.subscribe(None);
+        service.publish(vec![stock(1, 4)]);
+
+        assert!(missed.is_empty());
+        assert_eq!(receiver.recv().await.unwrap().id, 2);
+    }
+
+    #[test]
+    fn resuming_replays_missed_events() {
+        let service = service(3);
+        service.publish((1..=5).map(|n| stock(1, n)).collect());
+
+        assert_eq!(ids(&service.subscribe(Some(3)).0), vec![4, 5]);
+        assert_eq!(ids(&service.subscribe(Some(5)).0), Vec::<u64>::new());
+        // Older events are gone, newer IDs come from before a restart
+        assert_eq!(ids(&service.subscribe(Some(1)).0), vec![3, 4, 5]);
+        assert_eq!(ids(&service.subscribe(Some(42)).0), vec![3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn closing_resolves_pending_and_later_waits() {
+        let service = service(10);
+        let pending = tokio::spawn(service.closed());
+
+        service.close();
+
+        tokio::time::timeout(Duration::from_secs(1), pending)
+            .await
+            .unwrap()
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(1), service.closed())
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::{BTreeMap, HashSet};
+
+use migration::{Alias, Migrator, MigratorTrait, Query};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr};
+
+use crate::models::health_model::{CheckModel, HealthModel, ReadinessModel, VersionModel};
+
+#[derive(Clone)]
+pub struct HealthService {
+    db: Option<DatabaseConnection>,
+}
+
+impl HealthService {
+    /// `db` is `None` when the service runs on stores that need no database.
+    pub fn new(db: Option<DatabaseConnection>) -> Self {
+        Self { db }
+    }
+
+    pub fn liveness(&self) -> HealthModel {
+        HealthModel {
+            status: "ok".to_string(),
+        }
+    }
+
+    pub async fn readiness(&self) -> ReadinessModel {
+        let mut checks = BTreeMap::new();
+
+        if let Some(db) = &self.db {
+            let database = match db.ping().await {
+                Ok(_) => passed(),
+                Err(err) => failed(format!("Database unreachable: {}", err)),
+            };
+            checks.insert("database".to_string(), database);
+
+            let migrations = match pending_migrations(db).await {
+                Ok(0) => passed(),
+                Ok(pending) => failed(format!("{} pending migration(s)", pending)),
+                Err(err) => failed(format!("Failed to read migration status: {}", err)),
+            };
+            checks.insert("migrations".to_string(), migrations);
+        }
+
+        let mut readiness = ReadinessModel {
+            status: "ready".to_string(),
+            checks,
+        };
+        if !readiness.is_ready() {
+            readiness.status = "degraded".to_string();
+        }
+
+        readiness
+    }
+
+    pub fn version(&self) -> VersionModel {
+        VersionModel {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_sha: env!("GIT_SHA").to_string(),
+            build_time: env!("BUILD_TIME").to_string(),
+        }
+    }
+}
+
+/// Counts the migrations not recorded in `seaql_migrations`. Unlike
+/// `Migrator::get_pending_migrations`, which creates that table when it is
+/// missing, this only reads, so probes never run DDL.
+async fn pending_migrations(db: &DatabaseConnection) -> Result<usize, DbErr> {
+    let query = Query::select()
+        .column(Alias::new("version"))
+        .from(Alias::new("seaql_migrations"))
+        .to_owned();
+    let applied = db
+        .query_all(db.get_database_backend().build(&query))
+        .await?
+        .iter()
+        .map(|row| row.try_get::<String>("", "version"))
+        .collect::<Result<HashSet<_>, _>>()?;
+
+    Ok(Migrator::migrations()
+        .iter()
+        .filter(|migration| !applied.contains(migration.name()))
+        .count())
+}
+
+fn passed() -> CheckModel {
+    CheckModel {
+        status: "ok".to_string(),
+        error: None,
+    }
+}
+
+fn failed(error: String) -> CheckModel {
+    CheckModel {
+        status: "fail".to_string(),
+        error: Some(error),
+    }
+}
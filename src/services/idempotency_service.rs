@@ -0,0 +1,256 @@
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDateTime, SubsecRound, Utc};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::{models::ErrorModel, repositories::store::IdempotencyStore};
+
+/// How long a stored response is replayed before its key can be reused.
+const KEY_TTL_HOURS: i64 = 24;
+
+/// How long a running request holds its key. A retry arriving later takes the
+/// key over, so a request that was dropped or crashed does not block it.
+const LOCK_TIMEOUT_SECS: i64 = 60;
+
+/// Time between two purges of expired keys.
+const PURGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// What to do with a request carrying an `Idempotency-Key`.
+#[derive(Debug, PartialEq)]
+pub enum IdempotencyOutcome {
+    /// First use of the key: run the request and `complete` it afterwards, or
+    /// `abandon` the claim held until `locked_until`.
+    Proceed { locked_until: NaiveDateTime },
+    /// The key already answered this request; send the same response again.
+    Replay { status_code: u16, body: String },
+    /// The key was used for a different request.
+    Mismatch,
+    /// The first request with this key has not finished yet and still holds
+    /// its claim on the key.
+    InProgress,
+}
+
+#[derive(Clone)]
+pub struct IdempotencyService {
+    idempotency_repository: Arc<dyn IdempotencyStore>,
+}
+
+impl IdempotencyService {
+    pub fn new(idempotency_repository: Arc<dyn IdempotencyStore>) -> Self {
+        Self {
+            idempotency_repository,
+        }
+    }
+
+    /// Claims `key` for the caller, or tells how an earlier use of it decides
+    /// this request.
+    pub async fn begin(
+        &self,
+        subject: &str,
+        key: &str,
+        fingerprint: &str,
+    ) -> Result<IdempotencyOutcome, ErrorModel> {
+        if key.is_empty() || key.len() > 255 {
+            return Err(ErrorModel::ValidationError(
+                "Idempotency-Key must be 1 to 255 characters".to_string(),
+            ));
+        }
+
+        let now = Utc::now().naive_utc();
+        // Postgres keeps microseconds, so `abandon` can match the stored value
+        let locked_until = (now + Duration::seconds(LOCK_TIMEOUT_SECS)).trunc_subsecs(6);
+
+        if let Some(record) = self
+            .idempotency_repository
+            .get_idempotency_key_from_db(subject, key)
+            .await?
+        {
+            let expires_at = record.created_at + Duration::hours(KEY_TTL_HOURS);
+            if expires_at > now {
+                if record.fingerprint != fingerprint {
+                    return Ok(IdempotencyOutcome::Mismatch);
+                }
+                if let (Some(status_code), Some(body)) = (record.status_code, record.response_body)
+                {
+                    return Ok(IdempotencyOutcome::Replay { status_code, body });
+                }
+
+                // Unanswered: proceed only once the first request's claim ran out
+                let taken_over = self
+                    .idempotency_repository
+                    .take_over_idempotency_key_in_db(subject, key, now, locked_until)
+                    .await?;
+                return Ok(if taken_over {
+                    IdempotencyOutcome::Proceed { locked_until }
+                } else {
+                    IdempotencyOutcome::InProgress
+                });
+            }
+
+            self.idempotency_repository
+                .delete_idempotency_key_in_db(subject, key)
+                .await?;
+        }
+
+        match self
+            .idempotency_repository
+            .create_idempotency_key_in_db(subject, key, fingerprint, locked_until)
+            .await?
+        {
+            true => Ok(IdempotencyOutcome::Proceed { locked_until }),
+            // Lost the race against a concurrent request with the same key
+            false => Ok(IdempotencyOutcome::InProgress),
+        }
+    }
+
+    /// Stores the response to replay for later uses of `key`.
+    pub async fn complete(
+        &self,
+        subject: &str,
+        key: &str,
+        status_code: u16,
+        body: String,
+    ) -> Result<(), ErrorModel> {
+        self.idempotency_repository
+            .complete_idempotency_key_in_db(subject, key, status_code, body)
+            .await
+    }
+
+    /// Releases `key` so the request can be retried, e.g. after a server error.
+    /// Does nothing once another request took the key over, as it then holds a
+    /// claim other than `locked_until`.
+    pub async fn abandon(
+        &self,
+        subject: &str,
+        key: &str,
+        locked_until: NaiveDateTime,
+    ) -> Result<(), ErrorModel> {
+        self.idempotency_repository
+            .release_idempotency_key_in_db(subject, key, locked_until)
+            .await
+    }
+
+    /// Deletes the keys whose responses are no longer replayed.
+    pub async fn purge_expired(&self) -> Result<u64, ErrorModel> {
+        let created_before = Utc::now().naive_utc() - Duration::hours(KEY_TTL_HOURS);
+        self.idempotency_repository
+            .purge_idempotency_keys_in_db(created_before)
+            .await
+    }
+
+    /// Purges expired keys in the background until the task is aborted.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PURGE_INTERVAL);
+            loop {
+                interval.tick().await;
+                match self.purge_expired().await {
+                    Ok(0) => {}
+                    Ok(purged) => info!("Purged {} expired idempotency keys", purged),
+                    Err(err) => warn!("Failed to purge idempotency keys: {:?}", err),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::in_memory::InMemoryStore;
+
+    fn service() -> IdempotencyService {
+        IdempotencyService::new(Arc::new(InMemoryStore::new()))
+    }
+
+    #[tokio::test]
+    async fn completed_key_replays_matching_requests_only() {
+        let service = service();
+
+        assert!(matches!(
+            service.begin("alice", "k1", "a").await.unwrap(),
+            IdempotencyOutcome::Proceed { .. }
+        ));
+        assert_eq!(
+            service.begin("alice", "k1", "a").await.unwrap(),
+            IdempotencyOutcome::InProgress
+        );
+
+        service
+            .complete("alice", "k1", 201, "{}".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            service.begin("alice", "k1", "a").await.unwrap(),
+            IdempotencyOutcome::Replay {
+                status_code: 201,
+                body: "{}".to_string()
+            }
+        );
+        assert_eq!(
+            service.begin("alice", "k1", "b").await.unwrap(),
+            IdempotencyOutcome::Mismatch
+        );
+        // Keys are scoped to the caller
+        assert!(matches!(
+            service.begin("bob", "k1", "b").await.unwrap(),
+            IdempotencyOutcome::Proceed { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn abandoned_key_can_be_retried() {
+        let service = service();
+
+        let IdempotencyOutcome::Proceed { locked_until } =
+            service.begin("alice", "k1", "a").await.unwrap()
+        else {
+            panic!("expected to claim the key");
+        };
+        service.abandon("alice", "k1", locked_until).await.unwrap();
+
+        assert!(matches!(
+            service.begin("alice", "k1", "a").await.unwrap(),
+            IdempotencyOutcome::Proceed { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn stale_claim_is_taken_over_once() {
+        let store = InMemoryStore::new();
+        let service = IdempotencyService::new(Arc::new(store.clone()));
+        // Left behind by a request that never finished
+        let expired = Utc::now().naive_utc() - Duration::seconds(1);
+        store
+            .create_idempotency_key_in_db("alice", "k1", "a", expired)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            service.begin("alice", "k1", "a").await.unwrap(),
+            IdempotencyOutcome::Proceed { .. }
+        ));
+        assert_eq!(
+            service.begin("alice", "k1", "a").await.unwrap(),
+            IdempotencyOutcome::InProgress
+        );
+        assert_eq!(
+            service.begin("alice", "k1", "b").await.unwrap(),
+            IdempotencyOutcome::Mismatch
+        );
+    }
+
+    #[tokio::test]
+    async fn purge_keeps_keys_still_replayed() {
+        let service = service();
+        service.begin("alice", "k1", "a").await.unwrap();
+
+        assert_eq!(service.purge_expired().await.unwrap(), 0);
+        assert_eq!(
+            service.begin("alice", "k1", "a").await.unwrap(),
+            IdempotencyOutcome::InProgress
+        );
+    }
+}
@@ -1,16 +1,23 @@
-use crate::{models::{item_model::{CreateItemModel, ItemModel, UpdateItemModel}, ErrorModel, NotFoundErrorModel}, repositories::item_repository::ItemRepository};
+use std::sync::Arc;
+
+use crate::{models::{audit_model::AuditContextModel, event_model::{InventoryChangeModel, StockLevelModel}, item_model::{CreateItemModel, ItemModel, StockSummaryModel, UpdateItemModel}, ErrorModel, NotFoundErrorModel}, repositories::store::ItemStore, services::event_stream_service::EventStreamService};
 
 #[derive(Clone)]
 pub struct ItemService {
-    item_repository: ItemRepository,
+    item_repository: Arc<dyn ItemStore>,
+    event_stream: EventStreamService,
 }
 
 impl ItemService {
-    pub fn new(item_repository: ItemRepository) -> Self {
-        Self { item_repository }
+    pub fn new(item_repository: Arc<dyn ItemStore>, event_stream: EventStreamService) -> Self {
+        Self { item_repository, event_stream }
     }
 
-    pub async fn create_item(&self, request: CreateItemModel) -> Result<ItemModel, ErrorModel> {
+    pub async fn create_item(
+        &self,
+        request: CreateItemModel,
+        context: &AuditContextModel,
+    ) -> Result<ItemModel, ErrorModel> {
         if request.product_id == 0 {
             return Err(ErrorModel::ValidationError("Product ID is required".to_string()));
         }else if request.color.is_empty() {
@@ -21,23 +28,169 @@ impl ItemService {
             return Err(ErrorModel::ValidationError("Stock is required".to_string()));
         }
 
-        self.item_repository.create_item_in_db(request).await
+        let item = self.item_repository.create_item_in_db(request, context).await?;
+        self.event_stream.publish(vec![InventoryChangeModel::ItemStockChanged(stock_level(&item))]);
+        Ok(item)
     }
 
-    pub async fn delete_item(&self, item_id: i32) -> Result<bool, NotFoundErrorModel> {
-        self.item_repository.delete_item_in_db(item_id).await
+    pub async fn delete_item(
+        &self,
+        item_id: i32,
+        context: &AuditContextModel,
+    ) -> Result<bool, NotFoundErrorModel> {
+        // Read first, the stream needs the product and stock of the gone item
+        let before = self.item_repository.get_item_by_id_from_db(item_id).await.ok();
+        let deleted = self.item_repository.delete_item_in_db(item_id, context).await?;
+        if let Some(before) = before {
+            self.event_stream.publish(vec![InventoryChangeModel::ItemDeleted(stock_level(&before))]);
+        }
+        Ok(deleted)
     }
 
     pub async fn update_item(
         &self,
         item_id: i32,
         request: UpdateItemModel,
+        context: &AuditContextModel,
     ) -> Result<ItemModel, NotFoundErrorModel> {
-        self.item_repository.update_item_in_db(item_id, request).await
+        let stock_given = request.stock.is_some();
+        let item = self.item_repository.update_item_in_db(item_id, request, context).await?;
+        if stock_given {
+            self.event_stream.publish(vec![InventoryChangeModel::ItemStockChanged(stock_level(&item))]);
+        }
+        Ok(item)
     }
 
 
     pub async fn get_item_by_id(&self, item_id: i32) -> Result<ItemModel, NotFoundErrorModel> {
         self.item_repository.get_item_by_id_from_db(item_id).await
     }
-}
\ No newline at end of file
+
+    pub async fn get_stock_summary(&self) -> Result<StockSummaryModel, ErrorModel> {
+        self.item_repository.get_stock_summary_from_db().await
+    }
+}
+
+pub(crate) fn stock_level(item: &ItemModel) -> StockLevelModel {
+    StockLevelModel {
+        item_id: item.id,
+        product_id: item.product_id,
+        stock: item.stock,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::Config,
+        models::product_model::CreateProductModal,
+        repositories::{in_memory::InMemoryStore, store::ProductStore},
+    };
+
+    fn context() -> AuditContextModel {
+        AuditContextModel::new("tester", None)
+    }
+
+    async fn service_with_product() -> (ItemService, i32) {
+        let store = InMemoryStore::new();
+        let product = store
+            .create_product_in_db(
+                CreateProductModal {
+                    name: "T-shirt".to_string(),
+                    description: None,
+                    items: Vec::new(),
+                },
+                &context(),
+            )
+            .await
+            .unwrap();
+
+        let event_stream = EventStreamService::new(&Config::default().event_stream);
+        (ItemService::new(Arc::new(store), event_stream), product.id)
+    }
+
+    fn item_request(product_id: i32, stock: i32) -> CreateItemModel {
+        CreateItemModel {
+            id: None,
+            product_id,
+            color: "red".to_string(),
+            stock,
+            size: "M".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn create_and_update_item() {
+        let (service, product_id) = service_with_product().await;
+
+        let item = service.create_item(item_request(product_id, 3), &context()).await.unwrap();
+        let updated = service
+            .update_item(
+                item.id,
+                UpdateItemModel {
+                    size: None,
+                    color: None,
+                    stock: Some(7),
+                },
+                &context(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated.stock, 7);
+        assert_eq!(updated.color, "red");
+    }
+
+    #[tokio::test]
+    async fn stock_changes_are_streamed() {
+        let (service, product_id) = service_with_product().await;
+        let (_, mut receiver) = service.event_stream.subscribe(None);
+
+        let item = service.create_item(item_request(product_id, 3), &context()).await.unwrap();
+        let color_only = UpdateItemModel {
+            size: None,
+            color: Some("blue".to_string()),
+            stock: None,
+        };
+        service.update_item(item.id, color_only, &context()).await.unwrap();
+        service.delete_item(item.id, &context()).await.unwrap();
+
+        let types: Vec<_> = [receiver.recv().await.unwrap(), receiver.recv().await.unwrap()]
+            .iter()
+            .map(|event| event.change.event_type())
+            .collect();
+        assert_eq!(types, vec!["ItemStockChanged", "ItemDeleted"]);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn create_item_requires_stock() {
+        let (service, product_id) = service_with_product().await;
+
+        let result = service.create_item(item_request(product_id, 0), &context()).await;
+
+        assert!(matches!(result, Err(ErrorModel::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn create_item_for_unknown_product_fails() {
+        let (service, product_id) = service_with_product().await;
+
+        let result = service.create_item(item_request(product_id + 1, 3), &context()).await;
+
+        assert!(matches!(result, Err(ErrorModel::DatabaseError(_))));
+    }
+
+    #[tokio::test]
+    async fn deleted_item_is_not_found() {
+        let (service, product_id) = service_with_product().await;
+        let item = service.create_item(item_request(product_id, 3), &context()).await.unwrap();
+
+        assert!(service.delete_item(item.id, &context()).await.unwrap());
+        assert!(matches!(
+            service.get_item_by_id(item.id).await,
+            Err(NotFoundErrorModel::NotFoundError(_))
+        ));
+    }
+}
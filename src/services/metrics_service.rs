@@ -0,0 +1,133 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend};
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::{
+    metrics::{self, DB_POOL_CONNECTIONS, DB_POOL_MAX_CONNECTIONS, ITEMS_OUT_OF_STOCK, ITEMS_TOTAL},
+    models::item_model::StockSummaryModel,
+};
+
+use super::item_service::ItemService;
+
+/// How long a stock summary is reused; `/metrics` is public, so scrapes
+/// must not be able to drive the counting queries.
+const STOCK_SUMMARY_MAX_AGE: Duration = Duration::from_secs(15);
+
+#[derive(Clone)]
+pub struct MetricsService {
+    item_service: ItemService,
+    db: Option<DatabaseConnection>,
+    max_connections: u32,
+    /// Latest stock summary and when it was counted.
+    stock_summary: Arc<Mutex<Option<(Instant, StockSummaryModel)>>>,
+}
+
+impl MetricsService {
+    pub fn new(item_service: ItemService, db: Option<DatabaseConnection>, max_connections: u32) -> Self {
+        Self {
+            item_service,
+            db,
+            max_connections,
+            stock_summary: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Refreshes the gauges sampled at scrape time and renders every metric.
+    /// When the stock cannot be counted, its gauges keep their last values.
+    pub async fn render(&self) -> String {
+        if let Some(summary) = self.stock_summary().await {
+            ITEMS_TOTAL.set(summary.total_items as i64);
+            ITEMS_OUT_OF_STOCK.set(summary.out_of_stock_items as i64);
+        }
+
+        if let Some(db) = &self.db {
+            if db.get_database_backend() == DbBackend::Postgres {
+                let pool = db.get_postgres_connection_pool();
+                let idle = pool.num_idle() as i64;
+
+                DB_POOL_CONNECTIONS.with_label_values(&["idle"]).set(idle);
+                DB_POOL_CONNECTIONS
+                    .with_label_values(&["in_use"])
+                    .set(pool.size() as i64 - idle);
+            }
+            DB_POOL_MAX_CONNECTIONS.set(self.max_connections as i64);
+        }
+
+        metrics::encode()
+    }
+
+    /// The cached stock summary, counted again once it is older than
+    /// `STOCK_SUMMARY_MAX_AGE`. Concurrent scrapes wait for one count.
+    async fn stock_summary(&self) -> Option<StockSummaryModel> {
+        let mut cached = self.stock_summary.lock().await;
+        if let Some((counted_at, summary)) = cached.as_ref() {
+            if counted_at.elapsed() < STOCK_SUMMARY_MAX_AGE {
+                return Some(summary.clone());
+            }
+        }
+
+        match self.item_service.get_stock_summary().await {
+            Ok(summary) => {
+                *cached = Some((Instant::now(), summary.clone()));
+                Some(summary)
+            }
+            Err(err) => {
+                warn!("Failed to count stock for metrics: {:?}", err);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::Config,
+        models::{audit_model::AuditContextModel, product_model::CreateProductModal},
+        repositories::{in_memory::InMemoryStore, store::ProductStore},
+        services::event_stream_service::EventStreamService,
+    };
+
+    /// Adds a product with one item out of stock.
+    async fn create_product(store: &InMemoryStore) {
+        store
+            .create_product_in_db(
+                CreateProductModal {
+                    name: "T-shirt".to_string(),
+                    description: None,
+                    items: serde_json::from_value(serde_json::json!([
+                        {"color": "red", "size": "M", "stock": 0}
+                    ]))
+                    .unwrap(),
+                },
+                &AuditContextModel::new("tester", None),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn stock_summary_is_reused_until_it_expires() {
+        let store = Arc::new(InMemoryStore::new());
+        let event_stream = EventStreamService::new(&Config::default().event_stream);
+        let service = MetricsService::new(ItemService::new(store.clone(), event_stream), None, 0);
+
+        create_product(&store).await;
+        assert_eq!(service.stock_summary().await.unwrap().total_items, 1);
+
+        create_product(&store).await;
+        assert_eq!(service.stock_summary().await.unwrap().total_items, 1);
+
+        // Pretend the summary was counted a while ago
+        service.stock_summary.lock().await.as_mut().unwrap().0 -= STOCK_SUMMARY_MAX_AGE;
+        let summary = service.stock_summary().await.unwrap();
+        assert_eq!(summary.total_items, 2);
+        assert_eq!(summary.out_of_stock_items, 2);
+    }
+}
@@ -1,2 +1,11 @@
+pub mod api_key_service;
+pub mod audit_service;
+pub mod auth_service;
+pub mod event_stream_service;
+pub mod health_service;
+pub mod idempotency_service;
+pub mod metrics_service;
 pub mod product_service;
-pub mod item_service;
\ No newline at end of file
+pub mod item_service;
+pub mod outbox_service;
+pub mod webhook_service;
@@ -0,0 +1,230 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::{
+    config::OutboxConfig,
+    models::{event_model::OutboxEventModel, ErrorModel},
+    repositories::store::OutboxStore,
+};
+
+/// Longest wait between two attempts at delivering an event.
+const MAX_RETRY_DELAY_SECS: i64 = 300;
+
+/// How long a dispatcher holds the events it claimed. Events it neither
+/// delivered nor failed by then, e.g. because it crashed, are claimed again.
+const CLAIM_TIMEOUT_SECS: i64 = 60;
+
+/// Destination of the events in the outbox, e.g. a message broker.
+///
+/// Delivery is at-least-once: an event is handed to a sink again when any
+/// sink failed it, so sinks should drop duplicates by event ID.
+#[async_trait::async_trait]
+pub trait EventSink: Send + Sync {
+    /// Name used in logs and delivery errors.
+    fn name(&self) -> &str;
+
+    async fn deliver(&self, event: &OutboxEventModel) -> Result<(), String>;
+}
+
+/// Writes every event to the log; the sink used when no other is configured.
+pub struct LogSink;
+
+#[async_trait::async_trait]
+impl EventSink for LogSink {
+    fn name(&self) -> &str {
+        "log"
+    }
+
+    async fn deliver(&self, event: &OutboxEventModel) -> Result<(), String> {
+        info!(
+            event_id = event.id,
+            event_type = %event.event_type,
+            product_id = event.product_id,
+            "Domain event published"
+        );
+        Ok(())
+    }
+}
+
+/// Moves committed events from the outbox to the sinks.
+#[derive(Clone)]
+pub struct OutboxDispatcher {
+    outbox_repository: Arc<dyn OutboxStore>,
+    sinks: Vec<Arc<dyn EventSink>>,
+    poll_interval: Duration,
+    batch_size: u64,
+}
+
+impl OutboxDispatcher {
+    pub fn new(
+        outbox_repository: Arc<dyn OutboxStore>,
+        sinks: Vec<Arc<dyn EventSink>>,
+        config: &OutboxConfig,
+    ) -> Self {
+        Self {
+            outbox_repository,
+            sinks,
+            poll_interval: config.poll_interval,
+            batch_size: config.batch_size,
+        }
+    }
+
+    /// Delivers one batch of pending events and returns how many of them
+    /// every sink accepted. Failed events are retried with a growing delay.
+    ///
+    /// Each batch is claimed first, so several instances can dispatch from
+    /// the same outbox without sending an event twice.
+    pub async fn dispatch_once(&self) -> Result<usize, ErrorModel> {
+        let locked_until = Utc::now().naive_utc() + chrono::Duration::seconds(CLAIM_TIMEOUT_SECS);
+        let events = self
+            .outbox_repository
+            .claim_pending_events_from_db(self.batch_size, locked_until)
+            .await?;
+
+        let mut delivered = 0;
+        for event in events {
+            let mut errors = Vec::new();
+            for sink in &self.sinks {
+                if let Err(err) = sink.deliver(&event).await {
+                    errors.push(format!("{}: {}", sink.name(), err));
+                }
+            }
+
+            if errors.is_empty() {
+                self.outbox_repository
+                    .mark_event_sent_in_db(event.id)
+                    .await?;
+                delivered += 1;
+            } else {
+                let attempts = event.attempts + 1;
+                let error = errors.join("; ");
+                warn!(event_id = event.id, attempts, %error, "Failed to deliver domain event");
+                self.outbox_repository
+                    .mark_event_failed_in_db(event.id, attempts, error, retry_at(attempts))
+                    .await?;
+            }
+        }
+
+        Ok(delivered)
+    }
+
+    /// Polls the outbox in the background until the task is aborted.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.poll_interval);
+            loop {
+                interval.tick().await;
+                if let Err(err) = self.dispatch_once().await {
+                    warn!("Failed to dispatch domain events: {:?}", err);
+                }
+            }
+        })
+    }
+}
+
+/// When to try an event again after its `attempts`-th failure: after 2, 4,
+/// 8, ... seconds, capped at `MAX_RETRY_DELAY_SECS`.
+pub(crate) fn retry_at(attempts: i32) -> chrono::NaiveDateTime {
+    let delay = 2i64
+        .checked_pow(attempts.clamp(0, 31) as u32)
+        .unwrap_or(MAX_RETRY_DELAY_SECS)
+        .min(MAX_RETRY_DELAY_SECS);
+    Utc::now().naive_utc() + chrono::Duration::seconds(delay)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{
+        models::{audit_model::AuditContextModel, product_model::CreateProductModal},
+        repositories::{in_memory::InMemoryStore, store::ProductStore},
+    };
+
+    /// Remembers delivered event types and fails while `failing` is set.
+    #[derive(Default)]
+    struct RecordingSink {
+        delivered: Mutex<Vec<String>>,
+        failing: Mutex<bool>,
+    }
+
+    #[async_trait::async_trait]
+    impl EventSink for RecordingSink {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn deliver(&self, event: &OutboxEventModel) -> Result<(), String> {
+            if *self.failing.lock().unwrap() {
+                return Err("unavailable".to_string());
+            }
+            self.delivered
+                .lock()
+                .unwrap()
+                .push(event.event_type.clone());
+            Ok(())
+        }
+    }
+
+    async fn store_with_product() -> Arc<InMemoryStore> {
+        let store = Arc::new(InMemoryStore::new());
+        store
+            .create_product_in_db(
+                CreateProductModal {
+                    name: "Shirt".to_string(),
+                    description: None,
+                    items: Vec::new(),
+                },
+                &AuditContextModel::new("alice", None),
+            )
+            .await
+            .unwrap();
+        store
+    }
+
+    fn dispatcher(store: Arc<InMemoryStore>, sink: Arc<RecordingSink>) -> OutboxDispatcher {
+        OutboxDispatcher::new(
+            store,
+            vec![sink],
+            &OutboxConfig {
+                poll_interval: Duration::from_secs(1),
+                batch_size: 10,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn delivered_events_are_not_sent_again() {
+        let sink = Arc::new(RecordingSink::default());
+        let dispatcher = dispatcher(store_with_product().await, sink.clone());
+
+        assert_eq!(dispatcher.dispatch_once().await.unwrap(), 1);
+        assert_eq!(dispatcher.dispatch_once().await.unwrap(), 0);
+        assert_eq!(*sink.delivered.lock().unwrap(), vec!["ProductCreated"]);
+    }
+
+    #[tokio::test]
+    async fn failed_events_are_retried_later() {
+        let sink = Arc::new(RecordingSink::default());
+        let dispatcher = dispatcher(store_with_product().await, sink.clone());
+
+        *sink.failing.lock().unwrap() = true;
+        assert_eq!(dispatcher.dispatch_once().await.unwrap(), 0);
+
+        // Backed off, so not due yet
+        *sink.failing.lock().unwrap() = false;
+        assert_eq!(dispatcher.dispatch_once().await.unwrap(), 0);
+        assert!(sink.delivered.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn retry_delay_is_capped() {
+        let delay = retry_at(40) - Utc::now().naive_utc();
+        assert!(delay <= chrono::Duration::seconds(MAX_RETRY_DELAY_SECS));
+        assert!(delay > chrono::Duration::seconds(MAX_RETRY_DELAY_SECS - 5));
+    }
+}
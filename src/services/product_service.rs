@@ -1,32 +1,72 @@
+use std::sync::Arc;
+
 use crate::{
     models::{
+        audit_model::AuditContextModel,
+        event_model::{InventoryChangeModel, ProductChangeModel},
         product_model::{
             CreateProductModal, ProductItemModel, UpdateProductModal, WholeProductModel,
         },
         ErrorModel, NotFoundErrorModel,
     },
-    repositories::product_repository::ProductRepository,
+    repositories::store::ProductStore,
+    services::{event_stream_service::EventStreamService, item_service::stock_level},
 };
 
 #[derive(Clone)]
 pub struct ProductService {
-    product_repository: ProductRepository,
+    product_repository: Arc<dyn ProductStore>,
+    event_stream: EventStreamService,
 }
 
 impl ProductService {
-    pub fn new(product_repository: ProductRepository) -> Self {
-        Self { product_repository }
+    pub fn new(
+        product_repository: Arc<dyn ProductStore>,
+        event_stream: EventStreamService,
+    ) -> Self {
+        Self {
+            product_repository,
+            event_stream,
+        }
     }
 
     pub async fn create_product(
         &self,
         request: CreateProductModal,
-    ) -> Result<WholeProductModel, ErrorModel> {
+        context: &AuditContextModel,
+    ) -> Result<ProductItemModel, ErrorModel> {
         if request.name.is_empty() {
             return Err(ErrorModel::ValidationError("Name is required".to_string()));
         }
 
-        self.product_repository.create_product_in_db(request).await
+        for item in &request.items {
+            if item.color.is_empty() {
+                return Err(ErrorModel::ValidationError("Item color is required".to_string()));
+            } else if item.size.is_empty() {
+                return Err(ErrorModel::ValidationError("Item size is required".to_string()));
+            } else if item.stock == 0 {
+                return Err(ErrorModel::ValidationError("Item stock is required".to_string()));
+            }
+        }
+
+        let product = self
+            .product_repository
+            .create_product_in_db(request, context)
+            .await?;
+
+        let mut changes = vec![InventoryChangeModel::ProductCreated(ProductChangeModel {
+            product_id: product.id,
+            name: Some(product.name.clone()),
+        })];
+        changes.extend(
+            product
+                .items
+                .iter()
+                .map(|item| InventoryChangeModel::ItemStockChanged(stock_level(item))),
+        );
+        self.event_stream.publish(changes);
+
+        Ok(product)
     }
 
     pub async fn get_all_products(&self) -> Result<Vec<ProductItemModel>, ErrorModel> {
@@ -37,15 +77,141 @@ impl ProductService {
         &self,
         product_id: i32,
         request: UpdateProductModal,
+        context: &AuditContextModel,
     ) -> Result<WholeProductModel, NotFoundErrorModel> {
-        self.product_repository
-            .update_product_in_db(product_id, request)
+        let product = self
+            .product_repository
+            .update_product_in_db(product_id, request, context)
+            .await?;
+        let change = ProductChangeModel {
+            product_id,
+            name: Some(product.name.clone()),
+        };
+        self.event_stream
+            .publish(vec![InventoryChangeModel::ProductUpdated(change)]);
+        Ok(product)
+    }
+
+    pub async fn delete_product(
+        &self,
+        product_id: i32,
+        context: &AuditContextModel,
+    ) -> Result<bool, NotFoundErrorModel> {
+        let deleted = self
+            .product_repository
+            .delete_product_in_db(product_id, context)
+            .await?;
+        let change = ProductChangeModel {
+            product_id,
+            name: None,
+        };
+        self.event_stream
+            .publish(vec![InventoryChangeModel::ProductDeleted(change)]);
+        Ok(deleted)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::Config,
+        models::item_model::CreateProductItemModel,
+        repositories::{in_memory::InMemoryStore, store::ItemStore},
+    };
+
+    fn context() -> AuditContextModel {
+        AuditContextModel::new("tester", None)
+    }
+
+    fn event_stream() -> EventStreamService {
+        EventStreamService::new(&Config::default().event_stream)
+    }
+
+    fn product_request(items: Vec<CreateProductItemModel>) -> CreateProductModal {
+        CreateProductModal {
+            name: "T-shirt".to_string(),
+            description: Some("Cotton".to_string()),
+            items,
+        }
+    }
+
+    fn item_request(color: &str, stock: i32) -> CreateProductItemModel {
+        CreateProductItemModel {
+            color: color.to_string(),
+            stock,
+            size: "M".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn create_product_with_items() {
+        let store = InMemoryStore::new();
+        let service = ProductService::new(Arc::new(store.clone()), event_stream());
+
+        let product = service
+            .create_product(
+                product_request(vec![item_request("red", 3), item_request("blue", 5)]),
+                &context(),
+            )
             .await
+            .unwrap();
+
+        assert_eq!(product.items.len(), 2);
+        assert!(product.items.iter().all(|item| item.product_id == product.id));
+        let stored = store.get_item_by_id_from_db(product.items[1].id).await.unwrap();
+        assert_eq!(stored.color, "blue");
+
+        let (streamed, _) = service.event_stream.subscribe(Some(0));
+        let types: Vec<_> = streamed.iter().map(|event| event.change.event_type()).collect();
+        assert_eq!(types, vec!["ProductCreated", "ItemStockChanged", "ItemStockChanged"]);
     }
 
-    pub async fn delete_product(&self, product_id: i32) -> Result<bool, NotFoundErrorModel> {
-        self.product_repository
-            .delete_product_in_db(product_id)
+    #[tokio::test]
+    async fn create_product_rejects_invalid_item() {
+        let store = InMemoryStore::new();
+        let service = ProductService::new(Arc::new(store.clone()), event_stream());
+
+        let result = service
+            .create_product(
+                product_request(vec![item_request("red", 3), item_request("", 5)]),
+                &context(),
+            )
+            .await;
+
+        assert!(matches!(result, Err(ErrorModel::ValidationError(_))));
+        assert!(service.get_all_products().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn update_missing_product_is_not_found() {
+        let service = ProductService::new(Arc::new(InMemoryStore::new()), event_stream());
+
+        let result = service
+            .update_product(
+                42,
+                UpdateProductModal {
+                    name: Some("Hoodie".to_string()),
+                    description: None,
+                },
+                &context(),
+            )
+            .await;
+
+        assert!(matches!(result, Err(NotFoundErrorModel::NotFoundError(_))));
+    }
+
+    #[tokio::test]
+    async fn delete_product_with_items_fails() {
+        let service = ProductService::new(Arc::new(InMemoryStore::new()), event_stream());
+        let product = service
+            .create_product(product_request(vec![item_request("red", 3)]), &context())
             .await
+            .unwrap();
+
+        let result = service.delete_product(product.id, &context()).await;
+
+        assert!(matches!(result, Err(NotFoundErrorModel::DatabaseError(_))));
     }
 }
@@ -0,0 +1,693 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+};
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    header::CONTENT_TYPE,
+    redirect::Policy,
+    Client, Url,
+};
+use sha2::Sha256;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::{
+    config::{OutboxConfig, WebhookConfig},
+    models::{
+        event_model::{OutboxEventModel, EVENT_TYPES},
+        webhook_model::{
+            CreateWebhookModel, IssuedWebhookModel, UpdateWebhookModel, WebhookDeliveryModel,
+            WebhookDeliveryStatus, WebhookModel,
+        },
+        ErrorModel, NotFoundErrorModel,
+    },
+    repositories::store::WebhookStore,
+};
+
+use super::outbox_service::{retry_at, EventSink};
+
+/// `sha256=` followed by the hex HMAC-SHA256 of `<timestamp>.<body>`.
+pub const SIGNATURE_HEADER: &str = "x-webhook-signature";
+/// Unix time the delivery was signed at.
+pub const TIMESTAMP_HEADER: &str = "x-webhook-timestamp";
+/// Type of the delivered event, e.g. `ItemStockChanged`.
+pub const EVENT_TYPE_HEADER: &str = "x-webhook-event";
+/// ID of the delivered event; the same on every retry.
+pub const EVENT_ID_HEADER: &str = "x-webhook-event-id";
+
+/// Shortest secret accepted from callers.
+const MIN_SECRET_LEN: usize = 16;
+
+/// Deliveries listed per subscription, newest first.
+const DELIVERY_LOG_LIMIT: u64 = 100;
+
+/// Slack on top of the worst-case time to send a claimed batch, before its
+/// unfinished deliveries can be claimed again.
+const CLAIM_MARGIN_SECS: i64 = 60;
+
+#[derive(Clone)]
+pub struct WebhookService {
+    webhook_repository: Arc<dyn WebhookStore>,
+    client: Client,
+    timeout: std::time::Duration,
+    max_attempts: i32,
+    allow_private_targets: bool,
+}
+
+impl WebhookService {
+    /// Fails when the HTTP client cannot be built, rather than sending
+    /// deliveries through one without the redirect and address guards.
+    pub fn new(
+        webhook_repository: Arc<dyn WebhookStore>,
+        config: &WebhookConfig,
+    ) -> Result<Self, String> {
+        // A redirect could lead anywhere, so a 3xx answer fails the delivery
+        let mut client = Client::builder()
+            .timeout(config.timeout)
+            .redirect(Policy::none());
+        if !config.allow_private_targets {
+            client = client.dns_resolver(Arc::new(PublicResolver));
+        }
+
+        let client = client
+            .build()
+            .map_err(|err| format!("Failed to build the webhook HTTP client: {}", err))?;
+
+        Ok(Self {
+            webhook_repository,
+            client,
+            timeout: config.timeout,
+            max_attempts: config.max_attempts,
+            allow_private_targets: config.allow_private_targets,
+        })
+    }
+
+    pub async fn create_webhook(
+        &self,
+        request: CreateWebhookModel,
+    ) -> Result<IssuedWebhookModel, ErrorModel> {
+        self.validate_url(&request.url)
+            .await
+            .map_err(ErrorModel::ValidationError)?;
+        validate_event_types(&request.event_types).map_err(ErrorModel::ValidationError)?;
+        let secret = match request.secret {
+            Some(secret) => {
+                validate_secret(&secret).map_err(ErrorModel::ValidationError)?;
+                secret
+            }
+            None => generate_secret(),
+        };
+
+        let webhook = self
+            .webhook_repository
+            .create_webhook_in_db(request.url, request.event_types, secret.clone())
+            .await?;
+
+        Ok(IssuedWebhookModel { webhook, secret })
+    }
+
+    pub async fn get_all_webhooks(&self) -> Result<Vec<WebhookModel>, ErrorModel> {
+        self.webhook_repository.get_all_webhooks_from_db().await
+    }
+
+    pub async fn get_webhook_by_id(
+        &self,
+        webhook_id: i32,
+    ) -> Result<WebhookModel, NotFoundErrorModel> {
+        self.webhook_repository
+            .get_webhook_by_id_from_db(webhook_id)
+            .await
+    }
+
+    pub async fn update_webhook(
+        &self,
+        webhook_id: i32,
+        webhook_data: UpdateWebhookModel,
+    ) -> Result<WebhookModel, NotFoundErrorModel> {
+        if let Some(url) = &webhook_data.url {
+            self.validate_url(url)
+                .await
+                .map_err(NotFoundErrorModel::ValidationError)?;
+        }
+        if let Some(event_types) = &webhook_data.event_types {
+            validate_event_types(event_types).map_err(NotFoundErrorModel::ValidationError)?;
+        }
+        if let Some(secret) = &webhook_data.secret {
+            validate_secret(secret).map_err(NotFoundErrorModel::ValidationError)?;
+        }
+
+        self.webhook_repository
+            .update_webhook_in_db(webhook_id, webhook_data)
+            .await
+    }
+
+    pub async fn delete_webhook(&self, webhook_id: i32) -> Result<bool, NotFoundErrorModel> {
+        self.webhook_repository
+            .delete_webhook_in_db(webhook_id)
+            .await
+    }
+
+    /// Latest deliveries of a subscription, newest first.
+    pub async fn get_webhook_deliveries(
+        &self,
+        webhook_id: i32,
+    ) -> Result<Vec<WebhookDeliveryModel>, NotFoundErrorModel> {
+        self.get_webhook_by_id(webhook_id).await?;
+
+        match self
+            .webhook_repository
+            .get_webhook_deliveries_from_db(webhook_id, DELIVERY_LOG_LIMIT)
+            .await
+        {
+            Ok(deliveries) => Ok(deliveries),
+            Err(ErrorModel::ValidationError(msg)) => Err(NotFoundErrorModel::ValidationError(msg)),
+            Err(ErrorModel::DatabaseError(msg)) => Err(NotFoundErrorModel::DatabaseError(msg)),
+        }
+    }
+
+    /// Attempts up to `limit` due deliveries and returns how many succeeded.
+    /// Failed ones are retried with a growing delay until `max_attempts` is
+    /// reached, then marked dead.
+    ///
+    /// The batch is claimed for as long as sending all of it may take, so
+    /// other instances delivering from the same database skip it.
+    pub async fn deliver_due(&self, limit: u64) -> Result<usize, ErrorModel> {
+        let deliveries = self
+            .webhook_repository
+            .claim_due_webhook_deliveries_from_db(limit, self.claimed_until(limit))
+            .await?;
+
+        let mut delivered = 0;
+        for mut delivery in deliveries {
+            let webhook = match self
+                .webhook_repository
+                .get_webhook_by_id_from_db(delivery.subscription_id)
+                .await
+            {
+                Ok(webhook) => webhook,
+                // Deleted since the delivery was loaded
+                Err(NotFoundErrorModel::NotFoundError(_)) => continue,
+                Err(NotFoundErrorModel::ValidationError(msg))
+                | Err(NotFoundErrorModel::DatabaseError(msg)) => {
+                    return Err(ErrorModel::DatabaseError(msg))
+                }
+            };
+
+            delivery.attempts += 1;
+            match self.send(&webhook, &delivery).await {
+                Ok(status) => {
+                    delivery.status = WebhookDeliveryStatus::Delivered.as_str().to_string();
+                    delivery.response_status = Some(status);
+                    delivery.last_error = None;
+                    delivery.delivered_at = Some(Utc::now().naive_utc());
+                    delivered += 1;
+                }
+                Err((status, error)) => {
+                    warn!(
+                        delivery_id = delivery.id,
+                        attempts = delivery.attempts,
+                        %error,
+                        "Failed to deliver webhook"
+                    );
+                    delivery.response_status = status;
+                    delivery.last_error = Some(error);
+                    if delivery.attempts >= self.max_attempts {
+                        delivery.status = WebhookDeliveryStatus::Dead.as_str().to_string();
+                    } else {
+                        delivery.next_attempt_at = retry_at(delivery.attempts);
+                    }
+                }
+            }
+
+            self.webhook_repository
+                .update_webhook_delivery_in_db(delivery)
+                .await?;
+        }
+
+        Ok(delivered)
+    }
+
+    /// Attempts due deliveries in the background, as often as the outbox is
+    /// polled, until the task is aborted.
+    pub fn spawn(self, config: &OutboxConfig) -> JoinHandle<()> {
+        let poll_interval = config.poll_interval;
+        let batch_size = config.batch_size;
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(poll_interval);
+            loop {
+                interval.tick().await;
+                if let Err(err) = self.deliver_due(batch_size).await {
+                    warn!("Failed to deliver webhooks: {:?}", err);
+                }
+            }
+        })
+    }
+
+    /// End of the claim on a batch of `limit` deliveries, capped at a day.
+    fn claimed_until(&self, limit: u64) -> chrono::NaiveDateTime {
+        let batch_timeout = u32::try_from(limit)
+            .ok()
+            .and_then(|limit| self.timeout.checked_mul(limit))
+            .and_then(|timeout| chrono::Duration::from_std(timeout).ok())
+            .map_or(chrono::Duration::days(1), |timeout| {
+                timeout.min(chrono::Duration::days(1))
+            });
+
+        Utc::now().naive_utc() + batch_timeout + chrono::Duration::seconds(CLAIM_MARGIN_SECS)
+    }
+
+    /// POSTs the signed payload; returns the response status on success, and
+    /// the status, if any, with a description on failure.
+    async fn send(
+        &self,
+        webhook: &WebhookModel,
+        delivery: &WebhookDeliveryModel,
+    ) -> Result<i32, (Option<i32>, String)> {
+        // The host may resolve elsewhere than when the subscription was made
+        self.validate_url(&webhook.url)
+            .await
+            .map_err(|err| (None, err))?;
+
+        let body = delivery.payload.to_string();
+        let timestamp = Utc::now().timestamp().to_string();
+
+        let response = self
+            .client
+            .post(&webhook.url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(EVENT_ID_HEADER, delivery.event_id.to_string())
+            .header(EVENT_TYPE_HEADER, &delivery.event_type)
+            .header(TIMESTAMP_HEADER, &timestamp)
+            .header(SIGNATURE_HEADER, sign(&webhook.secret, &timestamp, &body))
+            .body(body)
+            .send()
+            .await;
+
+        match response {
+            Ok(response) if response.status().is_success() => {
+                info!(
+                    delivery_id = delivery.id,
+                    "Webhook delivered to {}", webhook.url
+                );
+                Ok(response.status().as_u16() as i32)
+            }
+            Ok(response) => {
+                let status = response.status();
+                Err((
+                    Some(status.as_u16() as i32),
+                    format!("Endpoint answered {}", status),
+                ))
+            }
+            Err(err) => Err((None, format!("Request failed: {}", err))),
+        }
+    }
+}
+
+/// Hands outbox events to the subscriptions interested in them.
+#[async_trait::async_trait]
+impl EventSink for WebhookService {
+    fn name(&self) -> &str {
+        "webhooks"
+    }
+
+    async fn deliver(&self, event: &OutboxEventModel) -> Result<(), String> {
+        match self
+            .webhook_repository
+            .queue_webhook_deliveries_in_db(event)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(ErrorModel::ValidationError(msg)) | Err(ErrorModel::DatabaseError(msg)) => Err(msg),
+        }
+    }
+}
+
+/// Value of the `X-Webhook-Signature` header for a body sent at `timestamp`;
+/// receivers recompute it with their copy of the secret.
+pub fn sign(secret: &str, timestamp: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any size");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+impl WebhookService {
+    /// Accepts http(s) URLs whose host is, or only resolves to, public
+    /// addresses, unless private targets are allowed.
+    async fn validate_url(&self, url: &str) -> Result<(), String> {
+        let parsed = match Url::parse(url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => {
+                parsed
+            }
+            _ => return Err(format!("{} is not an http(s) URL", url)),
+        };
+        if self.allow_private_targets {
+            return Ok(());
+        }
+
+        let host = parsed.host_str().unwrap_or_default();
+        match host.trim_start_matches('[').trim_end_matches(']').parse() {
+            Ok(ip) if is_public(ip) => Ok(()),
+            Ok(ip) => Err(format!("{} points to the non-public address {}", url, ip)),
+            Err(_) => {
+                let port = parsed.port_or_known_default().unwrap_or(80);
+                public_addresses(host, port).await.map(|_| ())
+            }
+        }
+    }
+}
+
+/// Resolver of the webhook client; it refuses hosts with non-public
+/// addresses, so a name re-pointed after validation is not called either.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_owned();
+        Box::pin(async move {
+            let addresses = public_addresses(&host, 0).await?;
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Addresses of `host`, or an error when it has none or any of them is not
+/// public.
+async fn public_addresses(host: &str, port: u16) -> Result<Vec<SocketAddr>, String> {
+    let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|err| format!("Failed to resolve {}: {}", host, err))?
+        .collect();
+
+    match addresses.iter().find(|address| !is_public(address.ip())) {
+        Some(address) => Err(format!(
+            "{} resolves to the non-public address {}",
+            host,
+            address.ip()
+        )),
+        None if addresses.is_empty() => Err(format!("{} has no addresses", host)),
+        None => Ok(addresses),
+    }
+}
+
+/// Whether `ip` is a globally routable unicast address. Loopback, private,
+/// link-local (which holds the cloud metadata endpoint 169.254.169.254),
+/// shared, documentation, multicast and reserved ranges are not.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => is_public_v6(ip),
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || a == 0
+        // Carrier-grade NAT, 100.64.0.0/10
+        || (a == 100 && (b & 0xc0) == 64)
+        // Benchmarking, 198.18.0.0/15
+        || (a == 198 && (b & 0xfe) == 18)
+        // Reserved, 240.0.0.0/4
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    // IPv4-mapped and NAT64 addresses reach the embedded IPv4 address
+    if let Some(v4) = ip.to_ipv4_mapped() {
+        return is_public_v4(v4);
+    }
+    let segments = ip.segments();
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let [.., a, b, c, d] = ip.octets();
+        return is_public_v4(Ipv4Addr::new(a, b, c, d));
+    }
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local, fc00::/7
+        || (segments[0] & 0xfe00) == 0xfc00
+        // Link-local, fe80::/10, and the deprecated site-local fec0::/10
+        || (segments[0] & 0xffc0) == 0xfe80
+        || (segments[0] & 0xffc0) == 0xfec0
+        // Documentation, 2001:db8::/32
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8))
+}
+
+fn validate_event_types(event_types: &[String]) -> Result<(), String> {
+    if event_types.is_empty() {
+        return Err("Event types are required".to_string());
+    }
+
+    match event_types
+        .iter()
+        .find(|event_type| !EVENT_TYPES.contains(&event_type.as_str()))
+    {
+        Some(event_type) => Err(format!("Unknown event type {}", event_type)),
+        None => Ok(()),
+    }
+}
+
+fn validate_secret(secret: &str) -> Result<(), String> {
+    if secret.len() < MIN_SECRET_LEN {
+        return Err(format!(
+            "Secret must be at least {} characters",
+            MIN_SECRET_LEN
+        ));
+    }
+    Ok(())
+}
+
+fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("whsec_{}", hex::encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::repositories::in_memory::InMemoryStore;
+
+    /// Service accepting any target, so tests need no DNS lookups.
+    fn service() -> WebhookService {
+        WebhookService::new(Arc::new(InMemoryStore::new()), &webhook_config(true)).unwrap()
+    }
+
+    fn webhook_config(allow_private_targets: bool) -> WebhookConfig {
+        WebhookConfig {
+            timeout: Duration::from_secs(1),
+            max_attempts: 3,
+            allow_private_targets,
+        }
+    }
+
+    fn event() -> OutboxEventModel {
+        OutboxEventModel {
+            id: 7,
+            event_type: "ItemStockChanged".to_string(),
+            product_id: 1,
+            payload: serde_json::json!({"item_id": 1}),
+            created_at: Utc::now().naive_utc(),
+            attempts: 0,
+        }
+    }
+
+    fn request(url: &str, event_types: &[&str]) -> CreateWebhookModel {
+        CreateWebhookModel {
+            url: url.to_string(),
+            event_types: event_types
+                .iter()
+                .map(|event_type| event_type.to_string())
+                .collect(),
+            secret: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_subscriptions_are_rejected() {
+        let service = service();
+
+        for request in [
+            request("ftp://partner.example.com/hook", &["ItemStockChanged"]),
+            request("https://partner.example.com/hook", &[]),
+            request("https://partner.example.com/hook", &["StockMoved"]),
+        ] {
+            let result = service.create_webhook(request).await;
+            assert!(matches!(result, Err(ErrorModel::ValidationError(_))));
+        }
+
+        let issued = service
+            .create_webhook(request(
+                "https://partner.example.com/hook",
+                &["ItemStockChanged"],
+            ))
+            .await
+            .unwrap();
+        assert!(issued.secret.starts_with("whsec_"));
+    }
+
+    #[tokio::test]
+    async fn events_are_queued_for_matching_active_subscriptions_once() {
+        let service = service();
+        let stock = service
+            .create_webhook(request("https://a.example.com/hook", &["ItemStockChanged"]))
+            .await
+            .unwrap();
+        let paused = service
+            .create_webhook(request("https://b.example.com/hook", &["ItemStockChanged"]))
+            .await
+            .unwrap();
+        service
+            .update_webhook(
+                paused.webhook.id,
+                UpdateWebhookModel {
+                    active: Some(false),
+                    ..UpdateWebhookModel::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let event = event();
+        service.deliver(&event).await.unwrap();
+        service.deliver(&event).await.unwrap();
+
+        let deliveries = service
+            .get_webhook_deliveries(stock.webhook.id)
+            .await
+            .unwrap();
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].status, "pending");
+        assert!(service
+            .get_webhook_deliveries(paused.webhook.id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn claimed_deliveries_are_left_to_their_claimant() {
+        let store = Arc::new(InMemoryStore::new());
+        let service = WebhookService::new(store.clone(), &webhook_config(true)).unwrap();
+        let issued = service
+            .create_webhook(request("https://a.example.com/hook", &["ItemStockChanged"]))
+            .await
+            .unwrap();
+        service.deliver(&event()).await.unwrap();
+
+        // Another instance claimed the delivery and is still sending it
+        let claimed = store
+            .claim_due_webhook_deliveries_from_db(10, service.claimed_until(10))
+            .await
+            .unwrap();
+        assert_eq!(claimed.len(), 1);
+
+        assert_eq!(service.deliver_due(10).await.unwrap(), 0);
+        let deliveries = service
+            .get_webhook_deliveries(issued.webhook.id)
+            .await
+            .unwrap();
+        assert_eq!(deliveries[0].attempts, 0);
+        assert_eq!(deliveries[0].status, "pending");
+    }
+
+    #[tokio::test]
+    async fn non_public_targets_are_rejected() {
+        let service =
+            WebhookService::new(Arc::new(InMemoryStore::new()), &webhook_config(false)).unwrap();
+
+        for url in [
+            "http://127.0.0.1:8080/hook",
+            "http://localhost/hook",
+            "http://10.0.0.5/hook",
+            "http://192.168.1.1/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://100.64.0.1/hook",
+            "http://0.0.0.0/hook",
+            "http://[::1]/hook",
+            "http://[fd00::1]/hook",
+            "http://[fe80::1]/hook",
+            "http://[::ffff:169.254.169.254]/hook",
+        ] {
+            let result = service
+                .create_webhook(request(url, &["ItemStockChanged"]))
+                .await;
+            assert!(
+                matches!(result, Err(ErrorModel::ValidationError(_))),
+                "{} was accepted",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn only_public_addresses_are_public() {
+        for ip in ["93.184.216.34", "8.8.8.8", "2606:4700::1111"] {
+            assert!(is_public(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "172.16.0.1",
+            "198.18.0.1",
+            "240.0.0.1",
+            "64:ff9b::a00:1",
+            "2001:db8::1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn deliveries_to_non_public_targets_fail() {
+        let store = Arc::new(InMemoryStore::new());
+        let permissive = WebhookService::new(store.clone(), &webhook_config(true)).unwrap();
+        let issued = permissive
+            .create_webhook(request("http://127.0.0.1:9/hook", &["ItemStockChanged"]))
+            .await
+            .unwrap();
+        permissive.deliver(&event()).await.unwrap();
+
+        // e.g. a subscription stored before private targets were disallowed
+        let strict = WebhookService::new(store, &webhook_config(false)).unwrap();
+        assert_eq!(strict.deliver_due(10).await.unwrap(), 0);
+
+        let deliveries = strict
+            .get_webhook_deliveries(issued.webhook.id)
+            .await
+            .unwrap();
+        assert_eq!(deliveries[0].attempts, 1);
+        assert!(deliveries[0]
+            .last_error
+            .as_deref()
+            .unwrap()
+            .contains("non-public address 127.0.0.1"));
+    }
+
+    #[test]
+    fn signature_covers_timestamp_and_body() {
+        let signature = sign("secret", "1700000000", "{}");
+
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature, sign("secret", "1700000000", "{}"));
+        assert_ne!(signature, sign("secret", "1700000001", "{}"));
+        assert_ne!(signature, sign("other", "1700000000", "{}"));
+    }
+}
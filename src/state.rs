@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use axum::extract::FromRef;
+use sea_orm::DatabaseConnection;
+
+use crate::{
+    config::Config,
+    middleware::rate_limit::Limits,
+    repositories::{
+        api_key_repository::ApiKeyRepository,
+        audit_repository::AuditRepository,
+        idempotency_repository::IdempotencyRepository,
+        item_repository::ItemRepository,
+        product_repository::ProductRepository,
+        store::{
+            ApiKeyStore, AuditStore, IdempotencyStore, ItemStore, ProductStore, WebhookStore,
+        },
+        webhook_repository::WebhookRepository,
+    },
+    services::{
+        api_key_service::ApiKeyService, audit_service::AuditService, auth_service::AuthService,
+        event_stream_service::EventStreamService, health_service::HealthService,
+        idempotency_service::IdempotencyService, item_service::ItemService,
+        metrics_service::MetricsService, product_service::ProductService,
+        webhook_service::WebhookService,
+    },
+};
+
+/// Shared state of the product API; handlers extract the parts they need.
+#[derive(Clone)]
+pub struct AppState {
+    pub config: Arc<Config>,
+    pub limits: Limits,
+    pub auth_service: AuthService,
+    pub api_key_service: ApiKeyService,
+    pub audit_service: AuditService,
+    pub idempotency_service: IdempotencyService,
+    pub product_service: ProductService,
+    pub item_service: ItemService,
+    pub webhook_service: WebhookService,
+    pub event_stream_service: EventStreamService,
+    pub health_service: HealthService,
+    pub metrics_service: MetricsService,
+}
+
+impl AppState {
+    pub fn new(config: Config, db: DatabaseConnection) -> Self {
+        let product_repository = ProductRepository::new(db.clone());
+        let item_repository = ItemRepository::new(db.clone());
+        let api_key_repository = ApiKeyRepository::new(db.clone());
+        let idempotency_repository = IdempotencyRepository::new(db.clone());
+        let audit_repository = AuditRepository::new(db.clone());
+        let webhook_repository = WebhookRepository::new(db.clone());
+
+        let state = Self::with_stores(
+            config,
+            Arc::new(product_repository),
+            Arc::new(item_repository),
+            Arc::new(api_key_repository),
+            Arc::new(idempotency_repository),
+            Arc::new(audit_repository),
+            Arc::new(webhook_repository),
+        );
+
+        Self {
+            health_service: HealthService::new(Some(db.clone())),
+            metrics_service: MetricsService::new(
+                state.item_service.clone(),
+                Some(db),
+                state.config.database.max_connections,
+            ),
+            ..state
+        }
+    }
+
+    /// Builds the state on top of custom stores, e.g. the in-memory one.
+    pub fn with_stores(
+        config: Config,
+        product_store: Arc<dyn ProductStore>,
+        item_store: Arc<dyn ItemStore>,
+        api_key_store: Arc<dyn ApiKeyStore>,
+        idempotency_store: Arc<dyn IdempotencyStore>,
+        audit_store: Arc<dyn AuditStore>,
+        webhook_store: Arc<dyn WebhookStore>,
+    ) -> Self {
+        let event_stream_service = EventStreamService::new(&config.event_stream);
+        let item_service = ItemService::new(item_store, event_stream_service.clone());
+        let api_key_service = ApiKeyService::new(api_key_store);
+
+        Self {
+            auth_service: AuthService::new(&config.auth, api_key_service.clone()),
+            api_key_service,
+            idempotency_service: IdempotencyService::new(idempotency_store),
+            audit_service: AuditService::new(audit_store),
+            // `main` builds a webhook service first and stops if that fails
+            webhook_service: WebhookService::new(webhook_store, &config.webhooks)
+                .expect("Failed to build the webhook HTTP client"),
+            limits: Limits::new(&config.limits),
+            config: Arc::new(config),
+            product_service: ProductService::new(product_store, event_stream_service.clone()),
+            event_stream_service,
+            metrics_service: MetricsService::new(item_service.clone(), None, 0),
+            item_service,
+            health_service: HealthService::new(None),
+        }
+    }
+}
+
+impl FromRef<AppState> for AuthService {
+    fn from_ref(state: &AppState) -> Self {
+        state.auth_service.clone()
+    }
+}
+
+impl FromRef<AppState> for ApiKeyService {
+    fn from_ref(state: &AppState) -> Self {
+        state.api_key_service.clone()
+    }
+}
+
+impl FromRef<AppState> for AuditService {
+    fn from_ref(state: &AppState) -> Self {
+        state.audit_service.clone()
+    }
+}
+
+impl FromRef<AppState> for WebhookService {
+    fn from_ref(state: &AppState) -> Self {
+        state.webhook_service.clone()
+    }
+}
+
+impl FromRef<AppState> for EventStreamService {
+    fn from_ref(state: &AppState) -> Self {
+        state.event_stream_service.clone()
+    }
+}
+
+impl FromRef<AppState> for ProductService {
+    fn from_ref(state: &AppState) -> Self {
+        state.product_service.clone()
+    }
+}
+
+impl FromRef<AppState> for ItemService {
+    fn from_ref(state: &AppState) -> Self {
+        state.item_service.clone()
+    }
+}
+
+impl FromRef<AppState> for HealthService {
+    fn from_ref(state: &AppState) -> Self {
+        state.health_service.clone()
+    }
+}
+
+impl FromRef<AppState> for MetricsService {
+    fn from_ref(state: &AppState) -> Self {
+        state.metrics_service.clone()
+    }
+}
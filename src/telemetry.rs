@@ -0,0 +1,131 @@
+use axum::http::HeaderMap;
+use tracing::Span;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
+
+use crate::config::{Config, LogFormat};
+
+/// Handle on the installed tracing pipeline, flushed on shutdown.
+pub struct Telemetry {
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::TracerProvider>,
+}
+
+/// Installs the global subscriber: log lines in the configured format and,
+/// with the `otel` feature and an OTLP endpoint configured, span export.
+///
+/// Fails when the OTLP exporter cannot be created; nothing is installed then.
+pub fn init(config: &Config) -> Result<Telemetry, String> {
+    let fmt_layer = match config.log_format {
+        LogFormat::Text => tracing_subscriber::fmt::layer().boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer().json().boxed(),
+    };
+
+    #[cfg(feature = "otel")]
+    {
+        let provider = config
+            .otel
+            .endpoint
+            .as_deref()
+            .map(|endpoint| otel::tracer_provider(endpoint, &config.otel.service_name))
+            .transpose()
+            .map_err(|err| format!("Failed to create the OTLP exporter: {}", err))?;
+        let otel_layer = provider.as_ref().map(otel::layer);
+
+        tracing_subscriber::registry()
+            .with(fmt_layer)
+            .with(otel_layer)
+            .init();
+
+        Ok(Telemetry { provider })
+    }
+
+    #[cfg(not(feature = "otel"))]
+    {
+        tracing_subscriber::registry().with(fmt_layer).init();
+
+        Ok(Telemetry {})
+    }
+}
+
+impl Telemetry {
+    /// Exports the spans still buffered.
+    pub fn shutdown(self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = self.provider {
+            if let Err(err) = provider.shutdown() {
+                eprintln!("Failed to flush spans: {}", err);
+            }
+        }
+    }
+}
+
+/// Makes `span` a child of the W3C `traceparent` sent by the caller, if any.
+pub fn set_parent_from_headers(span: &Span, headers: &HeaderMap) {
+    #[cfg(feature = "otel")]
+    otel::set_parent(span, headers);
+
+    #[cfg(not(feature = "otel"))]
+    let _ = (span, headers);
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use axum::http::HeaderMap;
+    use opentelemetry::{global, propagation::Extractor, trace::TracerProvider as _, KeyValue};
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::{
+        propagation::TraceContextPropagator, runtime, trace::TracerProvider, Resource,
+    };
+    use tracing::{Span, Subscriber};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::{registry::LookupSpan, Layer};
+
+    pub fn tracer_provider(
+        endpoint: &str,
+        service_name: &str,
+    ) -> Result<TracerProvider, opentelemetry::trace::TraceError> {
+        let exporter = SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(endpoint)
+            .build()?;
+
+        let provider = TracerProvider::builder()
+            .with_batch_exporter(exporter, runtime::Tokio)
+            .with_resource(Resource::new([KeyValue::new(
+                "service.name",
+                service_name.to_string(),
+            )]))
+            .build();
+
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        global::set_tracer_provider(provider.clone());
+
+        Ok(provider)
+    }
+
+    pub fn layer<S>(provider: &TracerProvider) -> impl Layer<S>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        tracing_opentelemetry::layer().with_tracer(provider.tracer(env!("CARGO_PKG_NAME")))
+    }
+
+    pub fn set_parent(span: &Span, headers: &HeaderMap) {
+        let context = global::get_text_map_propagator(|propagator| {
+            propagator.extract(&HeaderExtractor(headers))
+        });
+        span.set_parent(context);
+    }
+
+    struct HeaderExtractor<'a>(&'a HeaderMap);
+
+    impl Extractor for HeaderExtractor<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|value| value.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().map(|key| key.as_str()).collect()
+        }
+    }
+}
@@ -1,11 +1,89 @@
-use sea_orm::{Database, DatabaseConnection};
+use std::time::Duration;
 
-use super::constants::DATABASE_URL;
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{
+    ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DbBackend, DbErr, Statement,
+    TransactionTrait,
+};
+use tokio::time::{sleep, Instant};
+use tracing::{info, log::LevelFilter, warn};
 
-pub async fn establish_connection() -> DatabaseConnection {
-    let conn_str = (DATABASE_URL).clone();
-        Database::connect(conn_str)
-            .await
-            .expect("Failed to connect to db")
+use crate::config::DatabaseConfig;
 
-}
\ No newline at end of file
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(500);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// Key of the Postgres advisory lock serialising migrations across replicas.
+const MIGRATION_LOCK_KEY: i64 = 0x7072_6f64_7563_7473;
+
+/// Connects to the database, retrying with exponential backoff until
+/// `startup_timeout` has elapsed so the service survives a database that is
+/// still starting up.
+pub async fn establish_connection(config: &DatabaseConfig) -> Result<DatabaseConnection, DbErr> {
+    let options = connect_options(config);
+    let deadline = Instant::now() + config.startup_timeout;
+    let mut delay = INITIAL_RETRY_DELAY;
+    let mut attempt = 1;
+
+    loop {
+        match Database::connect(options.clone()).await {
+            Ok(db) => {
+                info!("Connected to db after {} attempt(s)", attempt);
+                return Ok(db);
+            }
+            Err(err) if Instant::now() + delay < deadline => {
+                warn!(
+                    "Failed to connect to db (attempt {}), retrying in {:?}: {}",
+                    attempt, delay, err
+                );
+                sleep(delay).await;
+                delay = (delay * 2).min(MAX_RETRY_DELAY);
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+fn connect_options(config: &DatabaseConfig) -> ConnectOptions {
+    let mut options = ConnectOptions::new(config.url.clone());
+    options
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        .connect_timeout(config.connect_timeout)
+        .acquire_timeout(config.acquire_timeout)
+        .idle_timeout(config.idle_timeout)
+        .sqlx_logging(config.log_statements)
+        .sqlx_logging_level(LevelFilter::Debug)
+        // Every pooled connection resolves unqualified tables in the configured schema
+        .set_schema_search_path(config.schema.clone());
+
+    options
+}
+
+/// Applies every pending migration from the `migration` crate.
+///
+/// On Postgres the migrations run in one transaction holding an advisory lock,
+/// so replicas starting at the same time apply them exactly once.
+pub async fn run_migrations(db: &DatabaseConnection, schema: &str) -> Result<(), DbErr> {
+    if db.get_database_backend() != DbBackend::Postgres {
+        return Migrator::up(db, None).await;
+    }
+
+    let txn = db.begin().await?;
+    info!("Waiting for the migration lock");
+    txn.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "SELECT pg_advisory_xact_lock($1)",
+        [MIGRATION_LOCK_KEY.into()],
+    ))
+    .await?;
+
+    migration::create_schema(&txn, schema).await?;
+    let pending = Migrator::get_pending_migrations(&txn).await?.len();
+    Migrator::up(&txn, None).await?;
+    txn.commit().await?;
+
+    info!("Applied {} pending migration(s)", pending);
+    Ok(())
+}
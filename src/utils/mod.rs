@@ -1,2 +1,2 @@
-pub mod constants;
-pub mod db;
\ No newline at end of file
+pub mod db;
+pub mod shutdown;
\ No newline at end of file
@@ -0,0 +1,85 @@
+use axum::http::{Method, StatusCode};
+use common::{app, send, send_as, send_with_headers, token_with_roles};
+use serde_json::json;
+
+mod common;
+
+#[tokio::test]
+async fn api_keys_authenticate_machine_clients() {
+    let app = app().await;
+    let (_, product) = send(&app, Method::POST, "/product", Some(json!({"name": "T-shirt"}))).await;
+
+    let (status, issued) = send(
+        &app,
+        Method::POST,
+        "/api-keys",
+        Some(json!({"name": "partner", "scopes": ["inventory:write"]})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let key = issued["key"].as_str().unwrap().to_string();
+    let item = json!({"product_id": product["id"], "color": "red", "size": "M", "stock": 3});
+
+    let (status, _) = send_with_headers(
+        &app,
+        &[("x-api-key", &key)],
+        Method::POST,
+        "/item",
+        Some(item.clone()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, body) = send_with_headers(
+        &app,
+        &[("x-api-key", &key)],
+        Method::PUT,
+        &format!("/product/{}", product["id"]),
+        Some(json!({"name": "Hoodie"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "Missing permission catalog:write");
+
+    // The listing never reveals secrets, only when the key was last used
+    let (_, keys) = send(&app, Method::GET, "/api-keys", None).await;
+    assert!(keys[0].get("key").is_none());
+    assert!(keys[0].get("secret_hash").is_none());
+    assert!(keys[0]["last_used_at"].is_string());
+
+    let uri = format!("/api-keys/{}/rotate", issued["id"]);
+    let (status, rotated) = send(&app, Method::POST, &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send_with_headers(
+        &app,
+        &[("x-api-key", &key)],
+        Method::POST,
+        "/item",
+        Some(item.clone()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = send(&app, Method::DELETE, &format!("/api-keys/{}", issued["id"]), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send_with_headers(
+        &app,
+        &[("x-api-key", rotated["key"].as_str().unwrap())],
+        Method::POST,
+        "/item",
+        Some(item),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn only_admins_manage_api_keys() {
+    let app = app().await;
+    let editor = token_with_roles("editor", &["editor"]);
+
+    let (status, body) = send_as(&app, Some(&editor), Method::GET, "/api-keys", None).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "Missing permission api-keys:manage");
+}
@@ -0,0 +1,67 @@
+use axum::http::{Method, StatusCode};
+use common::{app, send, send_as, send_with_headers, token, token_with_roles};
+use serde_json::json;
+
+mod common;
+
+#[tokio::test]
+async fn changes_are_listed_per_entity() {
+    let app = app().await;
+    send(&app, Method::POST, "/product", Some(json!({"name": "T-shirt", "items": [{"color": "red", "size": "M", "stock": 5}]}))).await;
+
+    let authorization = format!("Bearer {}", token("tester"));
+    let headers = [("authorization", authorization.as_str()), ("x-request-id", "restock-7")];
+    send_with_headers(&app, &headers, Method::PUT, "/item/1", Some(json!({"stock": 9}))).await;
+
+    let (status, entries) = send(&app, Method::GET, "/audit?entity=item&id=1", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let entries = entries.as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["action"], "update");
+    assert_eq!(entries[0]["actor"], "tester");
+    assert_eq!(entries[0]["request_id"], "restock-7");
+    assert_eq!(entries[0]["before"]["stock"], 5);
+    assert_eq!(entries[0]["after"]["stock"], 9);
+    assert_eq!(entries[1]["action"], "create");
+    assert_eq!(entries[1]["before"], json!(null));
+
+    let (_, entries) = send(&app, Method::GET, "/audit?entity=product&id=1", None).await;
+    assert_eq!(entries[0]["after"]["name"], "T-shirt");
+
+    let (_, entries) = send(&app, Method::GET, "/audit?from=2999-01-01T00:00:00", None).await;
+    assert_eq!(entries, json!([]));
+}
+
+#[tokio::test]
+async fn reading_the_audit_log_needs_its_permission() {
+    let app = app().await;
+
+    let editor = token_with_roles("editor", &["editor"]);
+    let (status, body) = send_as(&app, Some(&editor), Method::GET, "/audit", None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "Missing permission audit:read");
+
+    let (status, _) = send(&app, Method::GET, "/audit?entity=order", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn newest_changes_come_first_and_older_ones_are_paged() {
+    let app = app().await;
+    let items: Vec<_> = (0..100).map(|n| json!({"color": "red", "size": n.to_string(), "stock": 1})).collect();
+    let (status, _) = send(&app, Method::POST, "/product", Some(json!({"name": "T-shirt", "items": items}))).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // The product and its 100 items make one entry more than a page
+    let (status, page) = send(&app, Method::GET, "/audit", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let page = page.as_array().unwrap();
+    assert_eq!(page.len(), 100);
+    assert!(page.windows(2).all(|pair| pair[0]["id"].as_i64() > pair[1]["id"].as_i64()));
+
+    let last_id = page[99]["id"].as_i64().unwrap();
+    let (_, next) = send(&app, Method::GET, &format!("/audit?before_id={}", last_id), None).await;
+    let next = next.as_array().unwrap();
+    assert_eq!(next.len(), 1);
+    assert!(next[0]["id"].as_i64().unwrap() < last_id);
+}
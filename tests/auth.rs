@@ -0,0 +1,139 @@
+use axum::http::{Method, StatusCode};
+use common::{app, app_with, config, send, send_as, send_with_headers, token, token_with_roles};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use serde_json::json;
+
+mod common;
+
+fn rs256_token(subject: &str) -> String {
+    let key = include_bytes!("fixtures/rsa_private.pem");
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some("test-key".to_string());
+    let claims = json!({
+        "sub": subject,
+        "permissions": ["catalog:write"],
+        "exp": chrono::Utc::now().timestamp() + 3600,
+    });
+
+    encode(&header, &claims, &EncodingKey::from_rsa_pem(key).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn writes_require_a_bearer_token() {
+    let app = app().await;
+    let product = json!({"name": "T-shirt"});
+
+    let (status, body) = send_as(&app, None, Method::POST, "/product", Some(product.clone())).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"], "Missing bearer token or API key");
+
+    let (status, _) = send_as(&app, Some("not-a-jwt"), Method::POST, "/product", Some(product)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = send_as(&app, None, Method::DELETE, "/item/1", None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Reads stay public
+    let (status, _) = send_as(&app, None, Method::GET, "/product", None).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn rs256_tokens_are_checked_against_the_jwks() {
+    let app = app().await;
+
+    let (status, _) = send_as(
+        &app,
+        Some(&rs256_token("partner")),
+        Method::POST,
+        "/product",
+        Some(json!({"name": "T-shirt"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, _) = send(&app, Method::GET, "/product", None).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn permissions_are_enforced_per_route() {
+    let app = app().await;
+    let (_, product) = send(&app, Method::POST, "/product", Some(json!({"name": "T-shirt"}))).await;
+    let (_, item) = send(
+        &app,
+        Method::POST,
+        "/item",
+        Some(json!({"product_id": product["id"], "color": "red", "size": "M", "stock": 3})),
+    )
+    .await;
+    let warehouse = token_with_roles("warehouse-staff", &["warehouse"]);
+    let editor = token_with_roles("editor", &["editor"]);
+
+    let (status, _) = send_as(
+        &app,
+        Some(&warehouse),
+        Method::PUT,
+        &format!("/item/{}", item["id"]),
+        Some(json!({"stock": 10})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+
+    let (status, body) = send_as(
+        &app,
+        Some(&warehouse),
+        Method::PUT,
+        &format!("/product/{}", product["id"]),
+        Some(json!({"name": "Hoodie"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "Missing permission catalog:write");
+
+    let (status, body) = send_as(
+        &app,
+        Some(&editor),
+        Method::DELETE,
+        &format!("/item/{}", item["id"]),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "Missing permission catalog:delete");
+}
+
+#[tokio::test]
+async fn only_the_bearer_scheme_is_accepted() {
+    let app = app().await;
+    let basic = format!("Basic {}", token("alice"));
+    let bearer = format!("bearer {}", token("alice"));
+
+    let (status, body) = send_with_headers(&app, &[("authorization", &basic)], Method::DELETE, "/item/1", None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"], "Expected a Bearer token");
+
+    // The scheme is case-insensitive
+    let (status, _) = send_with_headers(&app, &[("authorization", &bearer)], Method::DELETE, "/item/1", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn failed_authentications_are_throttled() {
+    let mut config = config();
+    config.limits.auth_failures_per_minute = 2;
+    let app = app_with(config).await;
+
+    for _ in 0..2 {
+        let (status, _) = send_as(&app, Some("not-a-jwt"), Method::GET, "/product", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+    let (status, _) = send_with_headers(&app, &[("x-api-key", "guessed")], Method::GET, "/product", None).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+    // Even valid credentials wait until the quota refills; anonymous reads do not
+    let (status, _) = send_as(&app, Some(&token("alice")), Method::GET, "/product", None).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    let (status, _) = send_as(&app, None, Method::GET, "/product", None).await;
+    assert_eq!(status, StatusCode::OK);
+}
@@ -0,0 +1,129 @@
+#![allow(dead_code)]
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+    Router,
+};
+use http_body_util::BodyExt;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use practice_rust::{config::AuthConfig, utils::db::run_migrations, Config};
+use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+/// Secret the test configuration accepts HS256 tokens for.
+pub const JWT_SECRET: &str = "test-secret";
+
+/// Default configuration plus the keys tokens from `token` are signed with.
+pub fn config() -> Config {
+    let jwks = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/jwks.json"))
+        .unwrap();
+
+    Config {
+        auth: AuthConfig {
+            jwt_secret: Some(JWT_SECRET.to_string()),
+            jwks: Some(serde_json::from_str(&jwks).unwrap()),
+            ..AuthConfig::default()
+        },
+        ..Config::default()
+    }
+}
+
+/// HS256 admin token for `subject`, valid for an hour.
+pub fn token(subject: &str) -> String {
+    token_with_roles(subject, &["admin"])
+}
+
+/// HS256 bearer token for `subject` holding `roles`, valid for an hour.
+pub fn token_with_roles(subject: &str, roles: &[&str]) -> String {
+    let claims = json!({
+        "sub": subject,
+        "roles": roles,
+        "exp": chrono::Utc::now().timestamp() + 3600,
+    });
+
+    encode(&Header::default(), &claims, &EncodingKey::from_secret(JWT_SECRET.as_bytes())).unwrap()
+}
+
+/// Opens a fresh in-memory SQLite database without running any migration.
+pub async fn database() -> DatabaseConnection {
+    // A single connection keeps every query on the same in-memory database
+    let mut options = ConnectOptions::new("sqlite::memory:");
+    options.max_connections(1).min_connections(1);
+
+    Database::connect(options).await.unwrap()
+}
+
+/// Spins up the full router on a fresh, migrated in-memory SQLite database.
+pub async fn app() -> Router {
+    app_with(config()).await
+}
+
+/// Same as `app`, with a custom configuration.
+pub async fn app_with(config: Config) -> Router {
+    let db = database().await;
+    run_migrations(&db, "public").await.unwrap();
+
+    practice_rust::build_router(config, db)
+}
+
+/// Sends a request authenticated as `tester`.
+pub async fn send(app: &Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    send_as(app, Some(&token("tester")), method, uri, body).await
+}
+
+/// Sends a request with the given bearer token, or none.
+pub async fn send_as(
+    app: &Router,
+    token: Option<&str>,
+    method: Method,
+    uri: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let authorization = token.map(|token| format!("Bearer {}", token));
+    let headers: Vec<(&str, &str)> = authorization
+        .iter()
+        .map(|value| ("authorization", value.as_str()))
+        .collect();
+
+    send_with_headers(app, &headers, method, uri, body).await
+}
+
+/// Sends a request with extra headers and no other credentials.
+pub async fn send_with_headers(
+    app: &Router,
+    headers: &[(&str, &str)],
+    method: Method,
+    uri: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut request = Request::builder().method(method).uri(uri);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let request = match body {
+        Some(body) => request
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+
+    (status, body)
+}
+
+pub async fn get_text(app: &Router, uri: &str) -> (StatusCode, String) {
+    let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+
+    (status, String::from_utf8(bytes.to_vec()).unwrap())
+}
@@ -0,0 +1,58 @@
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use common::{app_with, config};
+use tower::ServiceExt;
+
+mod common;
+
+async fn app_for_admin_origin() -> Router {
+    let mut config = config();
+    config.cors.origins = vec!["https://admin.example.com".to_string()];
+    config.cors.allow_credentials = true;
+
+    app_with(config).await
+}
+
+async fn preflight(app: &Router, origin: &str) -> axum::response::Response {
+    let request = Request::builder()
+        .method(Method::OPTIONS)
+        .uri("/product/1")
+        .header(header::ORIGIN, origin)
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "PATCH")
+        .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization")
+        .body(Body::empty())
+        .unwrap();
+
+    app.clone().oneshot(request).await.unwrap()
+}
+
+#[tokio::test]
+async fn configured_origin_may_send_credentials() {
+    let app = app_for_admin_origin().await;
+
+    let response = preflight(&app, "https://admin.example.com").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert_eq!(
+        headers.get_all(header::ACCESS_CONTROL_ALLOW_ORIGIN).iter().count(),
+        1
+    );
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], "https://admin.example.com");
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+    assert!(headers[header::ACCESS_CONTROL_ALLOW_METHODS].to_str().unwrap().contains("PATCH"));
+    assert!(headers[header::ACCESS_CONTROL_ALLOW_HEADERS].to_str().unwrap().contains("authorization"));
+    assert_eq!(headers[header::ACCESS_CONTROL_MAX_AGE], "600");
+}
+
+#[tokio::test]
+async fn other_origins_are_not_allowed() {
+    let app = app_for_admin_origin().await;
+
+    let response = preflight(&app, "https://evil.example.com").await;
+
+    assert!(response.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+}
@@ -0,0 +1,31 @@
+use axum::http::{Method, StatusCode};
+use common::{app, get_text, send};
+
+mod common;
+
+#[tokio::test]
+async fn openapi_document_lists_every_endpoint() {
+    let app = app().await;
+
+    let (status, spec) = send(&app, Method::GET, "/openapi.json", None).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(spec["openapi"].as_str().unwrap().starts_with("3.1"));
+    for path in ["/product", "/product/{id}", "/item", "/item/{id}", "/healthz", "/metrics"] {
+        assert!(spec["paths"][path].is_object(), "{} is not documented", path);
+    }
+    assert!(spec["paths"]["/item/{id}"]["delete"].is_object());
+    assert!(spec["components"]["schemas"]["CreateProductModal"].is_object());
+}
+
+#[tokio::test]
+async fn swagger_ui_is_served_and_linked() {
+    let app = app().await;
+
+    let (status, page) = get_text(&app, "/docs/").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(page.contains("swagger"));
+
+    let (_, welcome) = get_text(&app, "/").await;
+    assert!(welcome.contains(r#"href="/docs""#));
+}
@@ -0,0 +1,160 @@
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+    Router,
+};
+use common::{app, config, database, send, send_as, token};
+use http_body_util::BodyExt;
+use practice_rust::{app_router, utils::db::run_migrations, AppState};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+mod common;
+
+/// One parsed Server-Sent Event.
+#[derive(Debug)]
+struct SseEvent {
+    id: u64,
+    event: String,
+    data: Value,
+}
+
+/// Opens `GET {uri}` as an admin, sending `Last-Event-ID` when given.
+async fn open_stream(app: &Router, uri: &str, last_event_id: Option<u64>) -> Body {
+    let mut request = Request::builder()
+        .uri(uri)
+        .header("authorization", format!("Bearer {}", token("dashboard")));
+    if let Some(id) = last_event_id {
+        request = request.header("last-event-id", id.to_string());
+    }
+
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+    response.into_body()
+}
+
+/// Reads the next `count` events, failing when they take too long.
+async fn next_events(body: &mut Body, count: usize) -> Vec<SseEvent> {
+    let mut buffer = String::new();
+    let mut events = Vec::new();
+
+    while events.len() < count {
+        let frame = tokio::time::timeout(Duration::from_secs(5), body.frame())
+            .await
+            .expect("no event within 5 seconds")
+            .unwrap()
+            .unwrap();
+        buffer.push_str(std::str::from_utf8(frame.data_ref().unwrap()).unwrap());
+
+        while let Some(end) = buffer.find("\n\n") {
+            let block: String = buffer.drain(..end + 2).collect();
+            let field = |name: &str| {
+                block
+                    .lines()
+                    .find_map(|line| line.strip_prefix(name))
+                    .map(|value| value.trim_start().to_string())
+            };
+            // Keep-alive comments carry no fields
+            if let Some(id) = field("id:") {
+                events.push(SseEvent {
+                    id: id.parse().unwrap(),
+                    event: field("event:").unwrap(),
+                    data: serde_json::from_str(&field("data:").unwrap()).unwrap(),
+                });
+            }
+        }
+    }
+
+    events
+}
+
+async fn create_product(app: &Router, name: &str, stock: i32) {
+    let (status, _) = send(
+        app,
+        Method::POST,
+        "/product",
+        Some(json!({"name": name, "items": [{"color": "red", "size": "M", "stock": stock}]})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn stream_pushes_changes_of_the_requested_product() {
+    let app = app().await;
+    let mut stream = open_stream(&app, "/events/stream?product_id=2", None).await;
+
+    create_product(&app, "T-shirt", 5).await;
+    create_product(&app, "Hoodie", 3).await;
+    let (status, _) = send(&app, Method::PUT, "/item/2", Some(json!({"stock": 1}))).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+
+    let events = next_events(&mut stream, 3).await;
+    let types: Vec<_> = events.iter().map(|event| event.event.as_str()).collect();
+    assert_eq!(
+        types,
+        vec!["ProductCreated", "ItemStockChanged", "ItemStockChanged"]
+    );
+    assert_eq!(events[0].data, json!({"product_id": 2, "name": "Hoodie"}));
+    assert_eq!(
+        events[2].data,
+        json!({"item_id": 2, "product_id": 2, "stock": 1})
+    );
+    assert!(events.windows(2).all(|pair| pair[0].id < pair[1].id));
+}
+
+#[tokio::test]
+async fn reconnecting_with_last_event_id_replays_missed_changes() {
+    let app = app().await;
+    let mut stream = open_stream(&app, "/events/stream", None).await;
+
+    create_product(&app, "T-shirt", 5).await;
+    let seen = next_events(&mut stream, 2).await;
+    drop(stream);
+
+    // Changed while the client was away
+    send(&app, Method::PUT, "/item/1", Some(json!({"stock": 4}))).await;
+    send(&app, Method::DELETE, "/item/1", None).await;
+
+    let mut stream = open_stream(&app, "/events/stream", Some(seen[1].id)).await;
+    let missed = next_events(&mut stream, 2).await;
+
+    assert_eq!(missed[0].id, seen[1].id + 1);
+    assert_eq!(missed[0].event, "ItemStockChanged");
+    assert_eq!(missed[0].data["stock"], 4);
+    assert_eq!(missed[1].event, "ItemDeleted");
+    assert_eq!(missed[1].data["stock"], 4);
+}
+
+#[tokio::test]
+async fn stream_needs_inventory_read() {
+    let app = app().await;
+
+    let (status, _) = send_as(&app, None, Method::GET, "/events/stream", None).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn streams_end_when_the_server_shuts_down() {
+    let db = database().await;
+    run_migrations(&db, "public").await.unwrap();
+    let state = AppState::new(config(), db);
+    let app = app_router(state.clone());
+    let mut stream = open_stream(&app, "/events/stream", None).await;
+
+    state.event_stream_service.close();
+
+    let frame = tokio::time::timeout(Duration::from_secs(5), stream.frame())
+        .await
+        .expect("stream still open after shutdown");
+    assert!(frame.is_none());
+}
@@ -0,0 +1,69 @@
+use axum::http::{Method, StatusCode};
+use common::{app, config, database, send};
+use practice_rust::{build_router, utils::db::run_migrations};
+use sea_orm::ConnectionTrait;
+
+mod common;
+
+#[tokio::test]
+async fn liveness_reports_ok() {
+    let app = app().await;
+
+    let (status, body) = send(&app, Method::GET, "/healthz", None).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ok");
+}
+
+#[tokio::test]
+async fn readiness_checks_database_and_migrations() {
+    let app = app().await;
+
+    let (status, body) = send(&app, Method::GET, "/readyz", None).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ready");
+    assert_eq!(body["checks"]["database"]["status"], "ok");
+    assert_eq!(body["checks"]["migrations"]["status"], "ok");
+}
+
+#[tokio::test]
+async fn readiness_degrades_with_pending_migrations() {
+    let db = database().await;
+    let app = build_router(config(), db.clone());
+
+    let (status, body) = send(&app, Method::GET, "/readyz", None).await;
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["status"], "degraded");
+    assert_eq!(body["checks"]["migrations"]["status"], "fail");
+    // Probing must not create the migration table
+    assert!(db
+        .execute_unprepared("SELECT version FROM seaql_migrations")
+        .await
+        .is_err());
+
+    run_migrations(&db, "public").await.unwrap();
+    db.execute_unprepared(
+        "DELETE FROM seaql_migrations WHERE version = 'm20261019_000006_add_idempotency_key_lock'",
+    )
+    .await
+    .unwrap();
+
+    let (status, body) = send(&app, Method::GET, "/readyz", None).await;
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["checks"]["migrations"]["error"], "1 pending migration(s)");
+}
+
+#[tokio::test]
+async fn version_reports_build_information() {
+    let app = app().await;
+
+    let (status, body) = send(&app, Method::GET, "/version", None).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+    assert!(body["git_sha"].is_string());
+    assert!(body["build_time"].is_string());
+}
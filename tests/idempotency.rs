@@ -0,0 +1,224 @@
+use std::{collections::BTreeSet, sync::Arc, time::Duration};
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{Method, StatusCode},
+    middleware::{from_fn, Next},
+    routing::post,
+    Extension, Router,
+};
+use chrono::Utc;
+use common::{app, database, send, send_with_headers, token};
+use practice_rust::{
+    middleware::{auth::Caller, idempotency::idempotency},
+    models::auth_model::CallerModel,
+    repositories::idempotency_repository::IdempotencyRepository,
+    services::idempotency_service::{IdempotencyOutcome, IdempotencyService},
+    utils::db::run_migrations,
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+mod common;
+
+async fn send_with_key(
+    app: &Router,
+    key: &str,
+    method: Method,
+    uri: &str,
+    body: Value,
+) -> (StatusCode, Value) {
+    let authorization = format!("Bearer {}", token("tester"));
+    let headers = [
+        ("authorization", authorization.as_str()),
+        ("idempotency-key", key),
+    ];
+
+    send_with_headers(app, &headers, method, uri, Some(body)).await
+}
+
+#[tokio::test]
+async fn retried_create_returns_the_first_response() {
+    let app = app().await;
+    send(
+        &app,
+        Method::POST,
+        "/product",
+        Some(json!({"name": "T-shirt"})),
+    )
+    .await;
+    let item = json!({"product_id": 1, "size": "M", "color": "red", "stock": 5});
+
+    let (status, first) = send_with_key(&app, "retry-1", Method::POST, "/item", item.clone()).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, replayed) =
+        send_with_key(&app, "retry-1", Method::POST, "/item", item.clone()).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(replayed, first);
+
+    // The retry did not insert a second item
+    let (_, next) = send(&app, Method::POST, "/item", Some(item)).await;
+    assert_eq!(next["id"], 2);
+}
+
+#[tokio::test]
+async fn reused_key_with_another_body_is_rejected() {
+    let app = app().await;
+    send(
+        &app,
+        Method::POST,
+        "/product",
+        Some(json!({"name": "T-shirt"})),
+    )
+    .await;
+    send(
+        &app,
+        Method::POST,
+        "/item",
+        Some(json!({"product_id": 1, "size": "M", "color": "red", "stock": 5})),
+    )
+    .await;
+
+    let (status, _) = send_with_key(
+        &app,
+        "adjust-1",
+        Method::PUT,
+        "/item/1",
+        json!({"stock": 4}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+
+    let (status, body) = send_with_key(
+        &app,
+        "adjust-1",
+        Method::PUT,
+        "/item/1",
+        json!({"stock": 3}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(body["error"].as_str().unwrap().contains("Idempotency-Key"));
+
+    let (_, item) = send(&app, Method::GET, "/item/1", None).await;
+    assert_eq!(item["stock"], 4);
+}
+
+async fn repository() -> IdempotencyRepository {
+    let db = database().await;
+    run_migrations(&db, "public").await.unwrap();
+    IdempotencyRepository::new(db)
+}
+
+#[tokio::test]
+async fn dropped_request_releases_its_key() {
+    let repository = repository().await;
+    let service = IdempotencyService::new(Arc::new(repository.clone()));
+    let app = Router::new()
+        .route(
+            "/slow",
+            post(|| async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                StatusCode::CREATED
+            }),
+        )
+        .route_layer(from_fn(idempotency))
+        .layer(Extension(service))
+        .layer(from_fn(|mut request: Request, next: Next| async move {
+            request.extensions_mut().insert(Caller(CallerModel {
+                subject: "alice".to_string(),
+                permissions: BTreeSet::new(),
+            }));
+            next.run(request).await
+        }));
+
+    let request = Request::post("/slow")
+        .header("idempotency-key", "slow-1")
+        .body(Body::empty())
+        .unwrap();
+    // The client gives up while the handler is still running
+    let timed_out = tokio::time::timeout(Duration::from_millis(100), app.oneshot(request)).await;
+    assert!(timed_out.is_err());
+
+    let mut released = false;
+    for _ in 0..50 {
+        if repository
+            .get_idempotency_key_from_db("alice", "slow-1")
+            .await
+            .unwrap()
+            .is_none()
+        {
+            released = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(released);
+}
+
+#[tokio::test]
+async fn abandoning_a_claim_that_was_taken_over_keeps_the_new_one() {
+    let repository = repository().await;
+    let service = IdempotencyService::new(Arc::new(repository.clone()));
+    // The first request's claim runs out while it is still running
+    let expired = Utc::now().naive_utc() - chrono::Duration::seconds(1);
+    repository
+        .create_idempotency_key_in_db("alice", "slow-2", "a", expired)
+        .await
+        .unwrap();
+    assert!(matches!(
+        service.begin("alice", "slow-2", "a").await.unwrap(),
+        IdempotencyOutcome::Proceed { .. }
+    ));
+
+    // The first request fails afterwards, but the retry still holds the key
+    service.abandon("alice", "slow-2", expired).await.unwrap();
+
+    assert!(repository
+        .get_idempotency_key_from_db("alice", "slow-2")
+        .await
+        .unwrap()
+        .is_some());
+    assert_eq!(
+        service.begin("alice", "slow-2", "a").await.unwrap(),
+        IdempotencyOutcome::InProgress
+    );
+}
+
+#[tokio::test]
+async fn stale_claims_are_taken_over_and_old_keys_purged() {
+    let repository = repository().await;
+    let service = IdempotencyService::new(Arc::new(repository.clone()));
+    let now = Utc::now().naive_utc();
+    repository
+        .create_idempotency_key_in_db("alice", "stale", "a", now - chrono::Duration::seconds(1))
+        .await
+        .unwrap();
+    repository
+        .create_idempotency_key_in_db("alice", "held", "a", now + chrono::Duration::seconds(60))
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        service.begin("alice", "stale", "a").await.unwrap(),
+        IdempotencyOutcome::Proceed { .. }
+    ));
+    assert_eq!(
+        service.begin("alice", "stale", "a").await.unwrap(),
+        IdempotencyOutcome::InProgress
+    );
+    assert_eq!(
+        service.begin("alice", "held", "a").await.unwrap(),
+        IdempotencyOutcome::InProgress
+    );
+
+    // Nothing is old enough yet, but everything is older than a cutoff in the future
+    assert_eq!(service.purge_expired().await.unwrap(), 0);
+    let purged = repository
+        .purge_idempotency_keys_in_db(now + chrono::Duration::seconds(1))
+        .await
+        .unwrap();
+    assert_eq!(purged, 2);
+}
@@ -0,0 +1,72 @@
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use common::{app_with, config, send, token};
+use serde_json::json;
+use tower::ServiceExt;
+
+mod common;
+
+async fn get_products(app: &Router) -> axum::response::Response {
+    let request = Request::builder().uri("/product").body(Body::empty()).unwrap();
+
+    app.clone().oneshot(request).await.unwrap()
+}
+
+#[tokio::test]
+async fn reads_beyond_the_quota_are_rejected() {
+    let mut config = config();
+    config.limits.read_per_minute = 2;
+    let app = app_with(config).await;
+
+    let response = get_products(&app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["ratelimit-limit"], "2");
+    assert_eq!(response.headers()["ratelimit-remaining"], "1");
+
+    get_products(&app).await;
+    let response = get_products(&app).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers()[header::RETRY_AFTER], "30");
+    assert_eq!(response.headers()["ratelimit-remaining"], "0");
+
+    // Writes have their own quota
+    let (status, _) = send(&app, Method::POST, "/product", Some(json!({"name": "T-shirt"}))).await;
+    assert_eq!(status, StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn webhook_listings_count_as_reads() {
+    let mut config = config();
+    config.limits.write_per_minute = 1;
+    let app = app_with(config).await;
+
+    for _ in 0..3 {
+        let (status, _) = send(&app, Method::GET, "/webhooks", None).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    // The write quota is still untouched
+    let (status, _) = send(&app, Method::POST, "/product", Some(json!({"name": "T-shirt"}))).await;
+    assert_eq!(status, StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn oversized_bodies_are_rejected() {
+    let mut config = config();
+    config.limits.max_body_bytes = 64;
+    let app = app_with(config).await;
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/product")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token("tester")))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(json!({"name": "x".repeat(100)}).to_string()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
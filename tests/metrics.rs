@@ -0,0 +1,57 @@
+use axum::http::{Method, StatusCode};
+use common::{app, config, database, get_text, send};
+use practice_rust::{app_router, utils::db::run_migrations, AppState};
+use serde_json::json;
+
+mod common;
+
+#[tokio::test]
+async fn metrics_cover_http_database_and_stock() {
+    let app = app().await;
+
+    let (status, product) = send(
+        &app,
+        Method::POST,
+        "/product",
+        Some(json!({"name": "T-shirt", "items": [
+            {"color": "red", "size": "M", "stock": 3},
+            {"color": "blue", "size": "L", "stock": 5}
+        ]})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, _) = send(
+        &app,
+        Method::PUT,
+        &format!("/item/{}", product["items"][0]["id"]),
+        Some(json!({"stock": 0})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+
+    let (status, body) = get_text(&app, "/metrics").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("catalog_items_total 2"));
+    assert!(body.contains("catalog_items_out_of_stock 1"));
+    assert!(body.contains(
+        r#"http_requests_total{method="PUT",route="/item/:id",status="202"} 1"#
+    ));
+    assert!(body.contains(
+        r#"db_query_duration_seconds_count{method="create_product_in_db",outcome="ok",repository="product"} 1"#
+    ));
+}
+
+#[tokio::test]
+async fn metrics_are_served_while_the_database_is_down() {
+    let db = database().await;
+    run_migrations(&db, "public").await.unwrap();
+    let app = app_router(AppState::new(config(), db.clone()));
+    db.close_by_ref().await.unwrap();
+    get_text(&app, "/healthz").await;
+
+    let (status, body) = get_text(&app, "/metrics").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains(r#"http_requests_total{method="GET",route="/healthz",status="200"} 1"#));
+}
@@ -0,0 +1,124 @@
+use std::sync::{Arc, Mutex};
+
+use axum::http::{Method, StatusCode};
+use chrono::{Duration, Utc};
+use common::{config, database, send};
+use practice_rust::{
+    models::event_model::OutboxEventModel,
+    repositories::outbox_repository::OutboxRepository,
+    services::outbox_service::{EventSink, OutboxDispatcher},
+    utils::db::run_migrations,
+};
+use serde_json::json;
+
+mod common;
+
+#[derive(Default)]
+struct RecordingSink {
+    events: Mutex<Vec<OutboxEventModel>>,
+}
+
+#[async_trait::async_trait]
+impl EventSink for RecordingSink {
+    fn name(&self) -> &str {
+        "recording"
+    }
+
+    async fn deliver(&self, event: &OutboxEventModel) -> Result<(), String> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn committed_changes_are_dispatched_once() {
+    let db = database().await;
+    run_migrations(&db, "public").await.unwrap();
+    let config = config();
+    let app = practice_rust::build_router(config.clone(), db.clone());
+
+    let sink = Arc::new(RecordingSink::default());
+    let dispatcher = OutboxDispatcher::new(
+        Arc::new(OutboxRepository::new(db.clone())),
+        vec![sink.clone()],
+        &config.outbox,
+    );
+
+    let (status, _) = send(
+        &app,
+        Method::POST,
+        "/product",
+        Some(json!({"name": "T-shirt", "items": [{"color": "red", "size": "M", "stock": 5}]})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, _) = send(&app, Method::PUT, "/item/1", Some(json!({"stock": 2}))).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+
+    assert_eq!(dispatcher.dispatch_once().await.unwrap(), 4);
+    assert_eq!(dispatcher.dispatch_once().await.unwrap(), 0);
+
+    let events = sink.events.lock().unwrap();
+    let types: Vec<&str> = events
+        .iter()
+        .map(|event| event.event_type.as_str())
+        .collect();
+    assert_eq!(
+        types,
+        [
+            "ProductCreated",
+            "ItemCreated",
+            "ItemUpdated",
+            "ItemStockChanged"
+        ]
+    );
+    assert!(events.iter().all(|event| event.product_id == 1));
+    assert_eq!(events[0].payload["name"], "T-shirt");
+    assert_eq!(
+        events[3].payload,
+        json!({"item_id": 1, "product_id": 1, "previous_stock": 5, "stock": 2})
+    );
+}
+
+#[tokio::test]
+async fn claimed_events_are_held_until_the_claim_expires() {
+    let db = database().await;
+    run_migrations(&db, "public").await.unwrap();
+    let app = practice_rust::build_router(config(), db.clone());
+    let repository = OutboxRepository::new(db);
+
+    let (status, _) = send(
+        &app,
+        Method::POST,
+        "/product",
+        Some(json!({"name": "T-shirt"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // A dispatcher that never reports back loses its claim once it runs out
+    let expired = Utc::now().naive_utc() - Duration::seconds(1);
+    let claimed = repository
+        .claim_pending_events_from_db(10, expired)
+        .await
+        .unwrap();
+    assert_eq!(claimed.len(), 1);
+
+    let held = Utc::now().naive_utc() + Duration::seconds(60);
+    let reclaimed = repository
+        .claim_pending_events_from_db(10, held)
+        .await
+        .unwrap();
+    assert_eq!(ids(&reclaimed), ids(&claimed));
+
+    // Other dispatchers find nothing while the claim holds
+    let others = repository
+        .claim_pending_events_from_db(10, held)
+        .await
+        .unwrap();
+    assert!(others.is_empty());
+}
+
+fn ids(events: &[OutboxEventModel]) -> Vec<i32> {
+    events.iter().map(|event| event.id).collect()
+}
@@ -0,0 +1,71 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    middleware::from_fn,
+    routing::get,
+    Json, Router,
+};
+use common::app;
+use http_body_util::BodyExt;
+use practice_rust::middleware::request_id::request_id;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+mod common;
+
+#[tokio::test]
+async fn caller_request_id_is_echoed_in_header_and_error_body() {
+    let app = app().await;
+    let request = Request::builder()
+        .uri("/item/99")
+        .header("x-request-id", "checkout-42")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(response.headers()["x-request-id"], "checkout-42");
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["error"], "Item not found");
+    assert_eq!(body["request_id"], "checkout-42");
+}
+
+#[tokio::test]
+async fn request_id_is_generated_when_missing() {
+    let app = app().await;
+    let request = Request::builder().uri("/healthz").body(Body::empty()).unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let id = response.headers()["x-request-id"].to_str().unwrap();
+    assert_eq!(id.len(), 36);
+}
+
+#[tokio::test]
+async fn large_error_bodies_are_passed_on_untouched() {
+    let message = "x".repeat(100 * 1024);
+    let error = json!({"error": message});
+    let app = Router::new()
+        .route(
+            "/large",
+            get(move || async move { (StatusCode::BAD_REQUEST, Json(error)) }),
+        )
+        .layer(from_fn(request_id));
+    let request = Request::builder()
+        .uri("/large")
+        .header("x-request-id", "big-1")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response.headers()["x-request-id"], "big-1");
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["error"].as_str().unwrap().len(), 100 * 1024);
+    assert!(body.get("request_id").is_none());
+}
@@ -0,0 +1,189 @@
+use std::sync::Arc;
+
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use common::{app, config, send};
+use practice_rust::{app_router, repositories::in_memory::InMemoryStore, AppState};
+use serde_json::{json, Value};
+
+mod common;
+
+async fn create_product(app: &Router, items: Value) -> Value {
+    let (status, product) = send(
+        app,
+        Method::POST,
+        "/product",
+        Some(json!({"name": "T-shirt", "description": "Cotton", "items": items})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    product
+}
+
+#[tokio::test]
+async fn product_lifecycle() {
+    let app = app().await;
+
+    let product = create_product(&app, json!([])).await;
+    let id = product["id"].as_i64().unwrap();
+    assert_eq!(product["name"], "T-shirt");
+
+    let (status, product) = send(
+        &app,
+        Method::PUT,
+        &format!("/product/{}", id),
+        Some(json!({"name": "Hoodie", "description": null})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(product["name"], "Hoodie");
+    assert_eq!(product["description"], Value::Null);
+
+    let (status, products) = send(&app, Method::GET, "/product", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(products.as_array().unwrap().len(), 1);
+
+    let (status, _) = send(&app, Method::DELETE, &format!("/product/{}", id), None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, products) = send(&app, Method::GET, "/product", None).await;
+    assert!(products.as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn product_validation_and_not_found() {
+    let app = app().await;
+
+    let (status, body) = send(&app, Method::POST, "/product", Some(json!({"name": ""}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "Name is required");
+
+    let (status, _) = send(
+        &app,
+        Method::PUT,
+        "/product/99",
+        Some(json!({"name": "Hoodie"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(&app, Method::DELETE, "/product/99", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn product_created_with_items() {
+    let app = app().await;
+
+    let product = create_product(
+        &app,
+        json!([
+            {"color": "red", "size": "M", "stock": 3},
+            {"color": "blue", "size": "L", "stock": 5}
+        ]),
+    )
+    .await;
+    assert_eq!(product["items"].as_array().unwrap().len(), 2);
+
+    let (status, products) = send(&app, Method::GET, "/product", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let items = products[0]["items"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().any(|item| item["color"] == "blue"));
+
+    // Items still reference the product, so it cannot be removed yet
+    let (status, _) = send(
+        &app,
+        Method::DELETE,
+        &format!("/product/{}", product["id"]),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[tokio::test]
+async fn item_lifecycle() {
+    let app = app().await;
+    let product = create_product(&app, json!([])).await;
+
+    let (status, item) = send(
+        &app,
+        Method::POST,
+        "/item",
+        Some(json!({"product_id": product["id"], "color": "red", "size": "M", "stock": 3})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let uri = format!("/item/{}", item["id"]);
+
+    let (status, item) = send(&app, Method::PUT, &uri, Some(json!({"stock": 7}))).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(item["stock"], 7);
+    assert_eq!(item["color"], "red");
+
+    let (status, item) = send(&app, Method::GET, &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item["stock"], 7);
+
+    let (status, _) = send(&app, Method::DELETE, &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send(&app, Method::GET, &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn item_validation_and_not_found() {
+    let app = app().await;
+
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        "/item",
+        Some(json!({"product_id": 1, "color": "red", "size": "M", "stock": 0})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "Stock is required");
+
+    let (status, _) = send(
+        &app,
+        Method::POST,
+        "/item",
+        Some(json!({"product_id": 99, "color": "red", "size": "M", "stock": 3})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+
+    let (status, _) = send(&app, Method::PUT, "/item/99", Some(json!({"stock": 1}))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(&app, Method::DELETE, "/item/99", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn router_can_be_nested_in_another_app() {
+    let store = InMemoryStore::new();
+    let state = AppState::with_stores(
+        config(),
+        Arc::new(store.clone()),
+        Arc::new(store.clone()),
+        Arc::new(store.clone()),
+        Arc::new(store.clone()),
+        Arc::new(store.clone()),
+        Arc::new(store),
+    );
+    let app = Router::new().nest("/catalog", app_router(state));
+
+    let (status, _) = send(&app, Method::POST, "/catalog/product", Some(json!({"name": "T-shirt"}))).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, products) = send(&app, Method::GET, "/catalog/product", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(products[0]["name"], "T-shirt");
+}
@@ -0,0 +1,199 @@
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU16, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, Method, StatusCode},
+    routing::post,
+    Router,
+};
+use common::{app, config, database, send};
+use practice_rust::{
+    repositories::{outbox_repository::OutboxRepository, webhook_repository::WebhookRepository},
+    services::{
+        outbox_service::OutboxDispatcher,
+        webhook_service::{sign, WebhookService, SIGNATURE_HEADER, TIMESTAMP_HEADER},
+    },
+    utils::db::run_migrations,
+    Config,
+};
+use serde_json::{json, Value};
+
+mod common;
+
+/// Partner endpoint recording every request and answering with `status`.
+#[derive(Clone, Default)]
+struct Receiver {
+    requests: Arc<Mutex<Vec<(HeaderMap, String)>>>,
+    status: Arc<AtomicU16>,
+}
+
+impl Receiver {
+    async fn start(status: StatusCode) -> (Self, SocketAddr) {
+        let receiver = Receiver::default();
+        receiver.status.store(status.as_u16(), Ordering::SeqCst);
+
+        let app = Router::new()
+            .route("/hook", post(receive))
+            .with_state(receiver.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        (receiver, address)
+    }
+}
+
+async fn receive(State(receiver): State<Receiver>, headers: HeaderMap, body: String) -> StatusCode {
+    receiver.requests.lock().unwrap().push((headers, body));
+    StatusCode::from_u16(receiver.status.load(Ordering::SeqCst)).unwrap()
+}
+
+/// The API plus the outbox dispatcher and webhook deliverer `main` would run.
+async fn setup(mut config: Config) -> (Router, OutboxDispatcher, WebhookService) {
+    // The receivers listen on loopback
+    config.webhooks.allow_private_targets = true;
+    let db = database().await;
+    run_migrations(&db, "public").await.unwrap();
+
+    let webhook_service = WebhookService::new(
+        Arc::new(WebhookRepository::new(db.clone())),
+        &config.webhooks,
+    )
+    .unwrap();
+    let dispatcher = OutboxDispatcher::new(
+        Arc::new(OutboxRepository::new(db.clone())),
+        vec![Arc::new(webhook_service.clone())],
+        &config.outbox,
+    );
+
+    (
+        practice_rust::build_router(config, db),
+        dispatcher,
+        webhook_service,
+    )
+}
+
+#[tokio::test]
+async fn stock_changes_are_posted_signed() {
+    let (receiver, address) = Receiver::start(StatusCode::OK).await;
+    let (app, dispatcher, webhook_service) = setup(config()).await;
+
+    let url = format!("http://{}/hook", address);
+    let (status, webhook) = send(
+        &app,
+        Method::POST,
+        "/webhooks",
+        Some(json!({"url": url, "event_types": ["ItemStockChanged"]})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let secret = webhook["secret"].as_str().unwrap().to_string();
+
+    send(
+        &app,
+        Method::POST,
+        "/product",
+        Some(json!({"name": "T-shirt", "items": [{"color": "red", "size": "M", "stock": 5}]})),
+    )
+    .await;
+    send(&app, Method::PUT, "/item/1", Some(json!({"stock": 2}))).await;
+
+    dispatcher.dispatch_once().await.unwrap();
+    assert_eq!(webhook_service.deliver_due(10).await.unwrap(), 1);
+
+    let requests = receiver.requests.lock().unwrap().clone();
+    assert_eq!(requests.len(), 1);
+    let (headers, body) = &requests[0];
+    let timestamp = headers[TIMESTAMP_HEADER].to_str().unwrap();
+    assert_eq!(
+        headers[SIGNATURE_HEADER].to_str().unwrap(),
+        sign(&secret, timestamp, body)
+    );
+    assert_eq!(headers["x-webhook-event"], "ItemStockChanged");
+    let body: Value = serde_json::from_str(body).unwrap();
+    assert_eq!(body["event_type"], "ItemStockChanged");
+    assert_eq!(body["payload"]["stock"], 2);
+
+    let (status, deliveries) = send(&app, Method::GET, "/webhooks/1/deliveries", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(deliveries[0]["status"], "delivered");
+    assert_eq!(deliveries[0]["attempts"], 1);
+    assert_eq!(deliveries[0]["response_status"], 200);
+
+    let (_, webhooks) = send(&app, Method::GET, "/webhooks", None).await;
+    assert!(webhooks[0].get("secret").is_none());
+}
+
+#[tokio::test]
+async fn failing_deliveries_end_up_dead() {
+    let (receiver, address) = Receiver::start(StatusCode::INTERNAL_SERVER_ERROR).await;
+    let mut config = config();
+    config.webhooks.max_attempts = 1;
+    let (app, dispatcher, webhook_service) = setup(config).await;
+
+    let url = format!("http://{}/hook", address);
+    send(
+        &app,
+        Method::POST,
+        "/webhooks",
+        Some(json!({"url": url, "event_types": ["ProductCreated"]})),
+    )
+    .await;
+    send(
+        &app,
+        Method::POST,
+        "/product",
+        Some(json!({"name": "T-shirt"})),
+    )
+    .await;
+
+    dispatcher.dispatch_once().await.unwrap();
+    assert_eq!(webhook_service.deliver_due(10).await.unwrap(), 0);
+    assert_eq!(receiver.requests.lock().unwrap().len(), 1);
+
+    let (_, deliveries) = send(&app, Method::GET, "/webhooks/1/deliveries", None).await;
+    assert_eq!(deliveries[0]["status"], "dead");
+    assert_eq!(deliveries[0]["response_status"], 500);
+
+    // Dead deliveries are not attempted again
+    assert_eq!(webhook_service.deliver_due(10).await.unwrap(), 0);
+    assert_eq!(receiver.requests.lock().unwrap().len(), 1);
+
+    let (status, _) = send(
+        &app,
+        Method::PUT,
+        "/webhooks/1",
+        Some(json!({"event_types": ["StockMoved"]})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(&app, Method::DELETE, "/webhooks/1", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&app, Method::GET, "/webhooks/1/deliveries", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn private_targets_are_refused() {
+    let app = app().await;
+
+    let (status, body) = send(
+        &app,
+        Method::POST,
+        "/webhooks",
+        Some(json!({"url": "http://169.254.169.254/latest/meta-data", "event_types": ["ItemStockChanged"]})),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"]
+        .as_str()
+        .unwrap()
+        .contains("non-public address"));
+}